@@ -0,0 +1,133 @@
+//! Module for block types decoded from validator RPC responses.
+
+use std::io::{self, Read};
+
+use zcash_primitives::{
+    block::{BlockHash, BlockHeader},
+    transaction::Transaction,
+};
+use zcash_protocol::consensus::{BlockHeight, BranchId};
+
+/// Identifies a block by height or by hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockId {
+    /// Block height
+    Height(BlockHeight),
+    /// Block hash
+    Hash(BlockHash),
+}
+
+impl std::fmt::Display for BlockId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Height(height) => write!(f, "{}", height),
+            Self::Hash(hash) => write!(f, "{}", hash),
+        }
+    }
+}
+
+impl From<BlockHeight> for BlockId {
+    fn from(height: BlockHeight) -> Self {
+        Self::Height(height)
+    }
+}
+
+impl From<BlockHash> for BlockId {
+    fn from(hash: BlockHash) -> Self {
+        Self::Hash(hash)
+    }
+}
+
+/// A full block decoded from its consensus serialization.
+#[derive(Debug)]
+pub struct Block {
+    /// Block height
+    height: BlockHeight,
+    /// Block header
+    header: BlockHeader,
+    /// Block transactions, starting with the coinbase transaction
+    transactions: Vec<Transaction>,
+}
+
+impl Block {
+    /// Decodes a block at `height` from its consensus serialization.
+    ///
+    /// `branch_id` must be the consensus branch ID active at `height`.
+    pub fn read<R: Read>(
+        mut reader: R,
+        height: BlockHeight,
+        branch_id: BranchId,
+    ) -> io::Result<Self> {
+        let header = BlockHeader::read(&mut reader)?;
+        let transaction_count = read_compact_size(&mut reader)?;
+        let transactions = (0..transaction_count)
+            .map(|_| Transaction::read(&mut reader, branch_id))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(Block {
+            height,
+            header,
+            transactions,
+        })
+    }
+
+    /// Returns the block height.
+    pub fn height(&self) -> BlockHeight {
+        self.height
+    }
+
+    /// Returns the block hash.
+    pub fn hash(&self) -> BlockHash {
+        self.header.hash()
+    }
+
+    /// Returns the block header.
+    pub fn header(&self) -> &BlockHeader {
+        &self.header
+    }
+
+    /// Returns the block transactions, starting with the coinbase transaction.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+}
+
+/// Decodes a 32-byte hash from its RPC representation, a hex string in reversed byte order.
+pub(crate) fn hash_from_hex(hex_str: &str) -> Result<[u8; 32], String> {
+    let mut bytes: [u8; 32] = hex::decode(hex_str)
+        .map_err(|e| e.to_string())?
+        .try_into()
+        .map_err(|_| format!("invalid hash length: {hex_str}"))?;
+    bytes.reverse();
+    Ok(bytes)
+}
+
+/// Encodes a 32-byte hash in its RPC representation, a hex string in reversed byte order.
+pub(crate) fn hash_to_hex(hash: &[u8]) -> String {
+    let mut bytes = hash.to_vec();
+    bytes.reverse();
+    hex::encode(bytes)
+}
+
+fn read_compact_size<R: Read>(mut reader: R) -> io::Result<u64> {
+    let mut flag = [0u8; 1];
+    reader.read_exact(&mut flag)?;
+    match flag[0] {
+        0xfd => {
+            let mut bytes = [0u8; 2];
+            reader.read_exact(&mut bytes)?;
+            Ok(u16::from_le_bytes(bytes).into())
+        }
+        0xfe => {
+            let mut bytes = [0u8; 4];
+            reader.read_exact(&mut bytes)?;
+            Ok(u32::from_le_bytes(bytes).into())
+        }
+        0xff => {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            Ok(u64::from_le_bytes(bytes))
+        }
+        n => Ok(n.into()),
+    }
+}
@@ -0,0 +1,116 @@
+//! Module for snapshotting and restoring process state within a running test.
+
+use std::{io, path::Path};
+
+use getset::Getters;
+use tempfile::TempDir;
+
+/// Snapshot of a process' chain state, returned by [`crate::validator::Validator::checkpoint`] and
+/// [`crate::indexer::Indexer::checkpoint`].
+///
+/// The snapshot is deleted when the checkpoint is dropped.
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct Checkpoint {
+    /// Snapshot directory
+    snapshot_dir: TempDir,
+}
+
+/// Snapshot of the validator and indexer state of a [`crate::LocalNet`], returned by
+/// [`crate::LocalNet::checkpoint`].
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct LocalNetCheckpoint {
+    /// Validator checkpoint
+    validator: Checkpoint,
+    /// Indexer checkpoint
+    indexer: Checkpoint,
+    /// Additional indexer checkpoints, in the order the indexers were attached
+    additional_indexers: Vec<Checkpoint>,
+}
+
+impl LocalNetCheckpoint {
+    pub(crate) fn new(
+        validator: Checkpoint,
+        indexer: Checkpoint,
+        additional_indexers: Vec<Checkpoint>,
+    ) -> Self {
+        LocalNetCheckpoint {
+            validator,
+            indexer,
+            additional_indexers,
+        }
+    }
+}
+
+/// Returns a checkpoint with an empty snapshot, for processes with no state.
+pub(crate) fn empty() -> io::Result<Checkpoint> {
+    Ok(Checkpoint {
+        snapshot_dir: tempfile::tempdir()?,
+    })
+}
+
+/// Copies the contents of `dir` into a new snapshot.
+///
+/// The process using `dir` must be stopped.
+pub(crate) fn snapshot(dir: &Path) -> io::Result<Checkpoint> {
+    let checkpoint = empty()?;
+    copy_dir_contents(dir, checkpoint.snapshot_dir.path())?;
+
+    Ok(checkpoint)
+}
+
+/// Replaces the contents of `dir` with the contents of the `checkpoint`.
+///
+/// The process using `dir` must be stopped.
+pub(crate) fn restore(checkpoint: &Checkpoint, dir: &Path) -> io::Result<()> {
+    std::fs::remove_dir_all(dir)?;
+    std::fs::create_dir_all(dir)?;
+    copy_dir_contents(checkpoint.snapshot_dir.path(), dir)
+}
+
+/// Recursively copies the contents of `source` into `destination`.
+pub(crate) fn copy_dir_contents(source: &Path, destination: &Path) -> io::Result<()> {
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let entry_destination = destination.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            std::fs::create_dir_all(&entry_destination)?;
+            copy_dir_contents(&entry.path(), &entry_destination)?;
+        } else {
+            std::fs::copy(entry.path(), entry_destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn snapshot_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("regtest/blocks")).unwrap();
+        std::fs::write(dir.path().join("regtest/blocks/blk00000.dat"), "block 1").unwrap();
+
+        let checkpoint = super::snapshot(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join("regtest/blocks/blk00000.dat"), "block 2").unwrap();
+        std::fs::write(dir.path().join("regtest/blocks/blk00001.dat"), "block 3").unwrap();
+
+        super::restore(&checkpoint, dir.path()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("regtest/blocks/blk00000.dat")).unwrap(),
+            "block 1"
+        );
+        assert!(!dir.path().join("regtest/blocks/blk00001.dat").exists());
+    }
+
+    #[test]
+    fn snapshot_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(super::snapshot(&dir.path().join("missing")).is_err());
+    }
+}
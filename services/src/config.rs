@@ -6,7 +6,10 @@ use std::path::{Path, PathBuf};
 
 use portpicker::Port;
 
-use crate::network::{ActivationHeights, Network};
+use crate::{
+    indexer::ZainodBackend,
+    network::{ActivationHeights, Network},
+};
 
 /// Used in subtree roots tests in zaino_testutils.  Fix later.
 pub const ZCASHD_FILENAME: &str = "zcash.conf";
@@ -14,13 +17,20 @@ pub(crate) const ZEBRAD_FILENAME: &str = "zebrad.toml";
 pub(crate) const ZAINOD_FILENAME: &str = "zindexer.toml";
 pub(crate) const LIGHTWALLETD_FILENAME: &str = "lightwalletd.yml";
 
+pub(crate) const RPC_USER: &str = "xxxxxx";
+pub(crate) const RPC_PASSWORD: &str = "xxxxxx";
+
 /// Writes the Zcashd config file to the specified config directory.
 /// Returns the path to the config file.
+///
+/// `activation_heights` and `miner_address` are only written for the regtest network. Testnet and mainnet are
+/// configured to run from a pre-synced data directory without connecting to peers.
 pub(crate) fn zcashd(
     config_dir: &Path,
     rpc_port: Port,
     activation_heights: &ActivationHeights,
     miner_address: Option<&str>,
+    network: Network,
 ) -> std::io::Result<PathBuf> {
     let config_file_path = config_dir.join(ZCASHD_FILENAME);
     let mut config_file = File::create(config_file_path.clone())?;
@@ -33,8 +43,9 @@ pub(crate) fn zcashd(
     let nu5_activation_height = activation_heights.nu5;
     let nu6_activation_height = activation_heights.nu6;
 
-    config_file.write_all(format!("\
-### Blockchain Configuration
+    let blockchain_config = match network {
+        Network::Regtest => format!(
+            "\
 regtest=1
 nuparams=5ba81b19:{overwinter_activation_height} # Overwinter
 nuparams=76b809bb:{sapling_activation_height} # Sapling
@@ -42,7 +53,22 @@ nuparams=2bb40e60:{blossom_activation_height} # Blossom
 nuparams=f5b9230b:{heartwood_activation_height} # Heartwood
 nuparams=e9ff75a6:{canopy_activation_height} # Canopy
 nuparams=c2d6d0b4:{nu5_activation_height} # NU5 (Orchard)
-nuparams=c8e71055:{nu6_activation_height} # NU6
+nuparams=c8e71055:{nu6_activation_height} # NU6"
+        ),
+        Network::Testnet => "\
+testnet=1
+# Cached chains are loaded without connecting to peers
+connect=0"
+            .to_string(),
+        Network::Mainnet => "\
+# Cached chains are loaded without connecting to peers
+connect=0"
+            .to_string(),
+    };
+
+    config_file.write_all(format!("\
+### Blockchain Configuration
+{blockchain_config}
 
 ### MetaData Storage and Retrieval
 # txindex:
@@ -56,8 +82,8 @@ lightwalletd=1
 
 ### RPC Server Interface Options:
 # https://zcash.readthedocs.io/en/latest/rtd_pages/zcash_conf_guide.html#json-rpc-options
-rpcuser=xxxxxx
-rpcpassword=xxxxxx
+rpcuser={RPC_USER}
+rpcpassword={RPC_PASSWORD}
 rpcport={rpc_port}
 rpcallowip=127.0.0.1
 
@@ -66,7 +92,7 @@ rpcallowip=127.0.0.1
 listen=0"
             ).as_bytes())?;
 
-    if let Some(addr) = miner_address {
+    if let (Some(addr), Network::Regtest) = (miner_address, network) {
         config_file.write_all(
 
                 format!("\n\n\
@@ -84,11 +110,18 @@ minetolocalwallet=0 # This is set to false so that we can mine to a wallet, othe
 /// Returns the path to the config file.
 ///
 /// Canopy (and all earlier network upgrades) must have an activation height of 1 for zebrad regtest mode
+///
+/// The metrics and tracing endpoints are only enabled if `metrics_listen_port` and `tracing_listen_port` are
+/// specified. If `tracing_filter` is `None`, zebrad's default filter is used.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn zebrad(
     config_dir: PathBuf,
     cache_dir: PathBuf,
     network_listen_port: Port,
     rpc_listen_port: Port,
+    metrics_listen_port: Option<Port>,
+    tracing_filter: Option<&str>,
+    tracing_listen_port: Option<Port>,
     activation_heights: &ActivationHeights,
     miner_address: &str,
     network: Network,
@@ -106,6 +139,18 @@ pub(crate) fn zebrad(
 
     let network_string = network.to_string();
 
+    let metrics_config = match metrics_listen_port {
+        Some(port) => format!("\nendpoint_addr = \"127.0.0.1:{port}\""),
+        None => String::new(),
+    };
+    let mut tracing_config = String::new();
+    if let Some(filter) = tracing_filter {
+        tracing_config.push_str(&format!("\nfilter = {}", toml_string(filter)));
+    }
+    if let Some(port) = tracing_listen_port {
+        tracing_config.push_str(&format!("\nendpoint_addr = \"127.0.0.1:{port}\""));
+    }
+
     config_file.write_all(
         format!(
             "\
@@ -116,7 +161,7 @@ checkpoint_sync = true
 eviction_memory_time = \"1h\"
 tx_cost_limit = 80000000
 
-[metrics]
+[metrics]{metrics_config}
 
 [network]
 cache_dir = false
@@ -160,7 +205,7 @@ parallel_cpu_threads = 0
 buffer_limit = 128000
 force_use_color = false
 use_color = true
-use_journald = false"
+use_journald = false{tracing_config}"
         )
         .as_bytes(),
     )?;
@@ -199,18 +244,37 @@ debug_like_zcashd = true"
 
 /// Writes the Zainod config file to the specified config directory.
 /// Returns the path to the config file.
+///
+/// The block cache database is Zaino's compact block cache in `validator_cache_dir` for the FetchService backend and
+/// Zebrad's state in the `backend` cache directory for the StateService backend.
 pub(crate) fn zainod(
     config_dir: &Path,
     validator_cache_dir: PathBuf,
     listen_port: Port,
+    json_rpc_port: Option<Port>,
     validator_port: Port,
     network: Network,
+    backend: &ZainodBackend,
 ) -> std::io::Result<PathBuf> {
     let config_file_path = config_dir.join(ZAINOD_FILENAME);
     let mut config_file = File::create(config_file_path.clone())?;
 
-    let zaino_cache_dir = validator_cache_dir.join("zaino");
-    let chain_cache = zaino_cache_dir.to_str().unwrap();
+    let (backend_string, db_path) = match backend {
+        ZainodBackend::Fetch => ("fetch", validator_cache_dir.join("zaino")),
+        ZainodBackend::State { zebrad_cache_dir } => ("state", zebrad_cache_dir.clone()),
+    };
+    let chain_cache = db_path.to_str().unwrap();
+
+    let json_rpc_server = match json_rpc_port {
+        Some(port) => format!(
+            "\
+enable_json_server = true
+
+# JsonRPC server listen addr.
+json_rpc_listen_address = \"localhost:{port}\""
+        ),
+        None => "enable_json_server = false".to_string(),
+    };
 
     let network_string = network.to_string();
 
@@ -219,6 +283,15 @@ pub(crate) fn zainod(
             "\
 # Configuration for Zaino
 
+# Backend:
+
+# Type of backend to use. Options are:
+# - \"fetch\" - Uses JsonRPC client to fetch data (Zcashd, Zainod).
+# - \"state\" - Uses ReadStateService to fetch data (Zebrad).
+backend = \"{backend_string}\"
+
+
+
 # gRPC server config:
 
 # Zainod's gRPC server listen address.
@@ -239,6 +312,19 @@ tls_key_path = \"None\"
 
 
 
+# JsonRPC server config:
+
+# Enables JsonRPC server.
+{json_rpc_server}
+
+# Enables cookie-based authentication for JsonRPC server.
+enable_cookie_auth = false
+
+# Directory to store authentication cookie file.
+cookie_dir = \"None\"
+
+
+
 # JsonRPC client config:
 
 # Full node / validator listen address.
@@ -255,10 +341,10 @@ validator_cookie_auth = false
 validator_cookie_path = \"None\"
 
 # Optional full node / validator Username.
-validator_user = \"xxxxxx\"
+validator_user = \"{RPC_USER}\"
 
 # Optional full node / validator Password.
-validator_password = \"xxxxxx\"
+validator_password = \"{RPC_PASSWORD}\"
 
 
 
@@ -312,7 +398,7 @@ no_sync = true
 #
 # Only used by the FetchServic.
 # Used for testing.
-no_db = true
+no_db = false
 
 # Disables internal mempool and blockcache.
 #
@@ -328,14 +414,19 @@ no_db = true
 
 /// Writes the Lightwalletd config file to the specified config directory.
 /// Returns the path to the config file.
+///
+/// `zcashd_conf` is `None` in darkside mode, where Lightwalletd does not connect to a validator.
 #[allow(dead_code)]
 pub(crate) fn lightwalletd(
     config_dir: &Path,
     grpc_bind_addr_port: Port,
     log_file: PathBuf,
-    zcashd_conf: PathBuf,
+    zcashd_conf: Option<PathBuf>,
 ) -> std::io::Result<PathBuf> {
-    let zcashd_conf = zcashd_conf.to_str().unwrap();
+    let zcashd_conf_config = match zcashd_conf {
+        Some(zcashd_conf) => format!("\nzcash-conf-path: {}", zcashd_conf.to_str().unwrap()),
+        None => String::new(),
+    };
     let log_file = log_file.to_str().unwrap();
 
     let config_file_path = config_dir.join(LIGHTWALLETD_FILENAME);
@@ -347,8 +438,7 @@ pub(crate) fn lightwalletd(
 grpc-bind-addr: 127.0.0.1:{grpc_bind_addr_port}
 cache-size: 10
 log-file: {log_file}
-log-level: 10
-zcash-conf-path: {zcashd_conf}"
+log-level: 10{zcashd_conf_config}"
         )
         .as_bytes(),
     )?;
@@ -356,11 +446,49 @@ zcash-conf-path: {zcashd_conf}"
     Ok(config_file_path)
 }
 
+/// Encodes `value` as a TOML basic string, escaping quotes, backslashes and control characters.
+fn toml_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+/// Reads the network type from the Zcashd config file at `zcashd_conf`.
+///
+/// As with Zcashd, the network is regtest if `regtest=1` is set, testnet if `testnet=1` is set and mainnet otherwise.
+pub(crate) fn zcashd_conf_network(zcashd_conf: &Path) -> std::io::Result<Network> {
+    let config = std::fs::read_to_string(zcashd_conf)?;
+    let is_set = |option: &str| {
+        config.lines().any(|line| {
+            let setting = line.split('#').next().unwrap_or_default();
+            setting.replace(' ', "") == format!("{option}=1")
+        })
+    };
+
+    Ok(if is_set("regtest") {
+        Network::Regtest
+    } else if is_set("testnet") {
+        Network::Testnet
+    } else {
+        Network::Mainnet
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
-    use crate::{logs, network};
+    use crate::{indexer::ZainodBackend, logs, network};
 
     const EXPECTED_CONFIG: &str = "\
 ### Blockchain Configuration
@@ -407,7 +535,14 @@ listen=0";
             nu6: 7.into(),
         };
 
-        super::zcashd(config_dir.path(), 1234, &activation_heights, None).unwrap();
+        super::zcashd(
+            config_dir.path(),
+            1234,
+            &activation_heights,
+            None,
+            network::Network::Regtest,
+        )
+        .unwrap();
 
         assert_eq!(
             std::fs::read_to_string(config_dir.path().join(super::ZCASHD_FILENAME)).unwrap(),
@@ -433,6 +568,7 @@ listen=0";
             1234,
             &activation_heights,
             Some("test_addr_1234"),
+            network::Network::Regtest,
         )
         .unwrap();
 
@@ -447,6 +583,87 @@ minetolocalwallet=0 # This is set to false so that we can mine to a wallet, othe
         );
     }
 
+    #[test]
+    fn zcashd_testnet() {
+        let config_dir = tempfile::tempdir().unwrap();
+
+        super::zcashd(
+            config_dir.path(),
+            1234,
+            &network::ActivationHeights::default(),
+            Some("test_addr_1234"),
+            network::Network::Testnet,
+        )
+        .unwrap();
+
+        let (_, expected_rpc_config) = EXPECTED_CONFIG.split_once("\n\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(config_dir.path().join(super::ZCASHD_FILENAME)).unwrap(),
+            format!(
+                "\
+### Blockchain Configuration
+testnet=1
+# Cached chains are loaded without connecting to peers
+connect=0
+
+{expected_rpc_config}"
+            )
+        );
+    }
+
+    #[test]
+    fn zebrad_endpoints() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        super::zebrad(
+            config_dir.path().to_path_buf(),
+            cache_dir.path().to_path_buf(),
+            1234,
+            1235,
+            Some(1236),
+            Some("info,zebra_state=debug"),
+            Some(1237),
+            &network::ActivationHeights::default(),
+            "test_addr_1234",
+            network::Network::Regtest,
+        )
+        .unwrap();
+
+        let config =
+            std::fs::read_to_string(config_dir.path().join(super::ZEBRAD_FILENAME)).unwrap();
+        assert!(config.contains("[metrics]\nendpoint_addr = \"127.0.0.1:1236\"\n"));
+        assert!(config.ends_with(
+            "use_journald = false\nfilter = \"info,zebra_state=debug\"\nendpoint_addr = \"127.0.0.1:1237\""
+        ));
+    }
+
+    #[test]
+    fn zebrad_tracing_filter_escaped() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        super::zebrad(
+            config_dir.path().to_path_buf(),
+            cache_dir.path().to_path_buf(),
+            1234,
+            1235,
+            None,
+            Some("info,zebrad[{name=\"C:\\zebra\"}]=debug"),
+            None,
+            &network::ActivationHeights::default(),
+            "test_addr_1234",
+            network::Network::Regtest,
+        )
+        .unwrap();
+
+        let config =
+            std::fs::read_to_string(config_dir.path().join(super::ZEBRAD_FILENAME)).unwrap();
+        assert!(config.ends_with(
+            "use_journald = false\nfilter = \"info,zebrad[{name=\\\"C:\\\\zebra\\\"}]=debug\""
+        ));
+    }
+
     #[test]
     fn zainod() {
         let config_dir = tempfile::tempdir().unwrap();
@@ -459,8 +676,10 @@ minetolocalwallet=0 # This is set to false so that we can mine to a wallet, othe
             config_dir.path(),
             zaino_cache_dir,
             1234,
+            None,
             18232,
             network::Network::Regtest,
+            &ZainodBackend::Fetch,
         )
         .unwrap();
 
@@ -470,6 +689,15 @@ minetolocalwallet=0 # This is set to false so that we can mine to a wallet, othe
                 "\
 # Configuration for Zaino
 
+# Backend:
+
+# Type of backend to use. Options are:
+# - \"fetch\" - Uses JsonRPC client to fetch data (Zcashd, Zainod).
+# - \"state\" - Uses ReadStateService to fetch data (Zebrad).
+backend = \"fetch\"
+
+
+
 # gRPC server config:
 
 # Zainod's gRPC server listen address.
@@ -490,6 +718,19 @@ tls_key_path = \"None\"
 
 
 
+# JsonRPC server config:
+
+# Enables JsonRPC server.
+enable_json_server = false
+
+# Enables cookie-based authentication for JsonRPC server.
+enable_cookie_auth = false
+
+# Directory to store authentication cookie file.
+cookie_dir = \"None\"
+
+
+
 # JsonRPC client config:
 
 # Full node / validator listen address.
@@ -563,7 +804,7 @@ no_sync = true
 #
 # Only used by the FetchServic.
 # Used for testing.
-no_db = true
+no_db = false
 
 # Disables internal mempool and blockcache.
 #
@@ -574,6 +815,55 @@ no_db = true
         )
     }
 
+    #[test]
+    fn zainod_state_backend() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let zebrad_cache_dir = tempfile::tempdir().unwrap();
+        let zebrad_cache_path = zebrad_cache_dir.path().to_str().unwrap();
+
+        super::zainod(
+            config_dir.path(),
+            cache_dir.path().to_path_buf(),
+            1234,
+            None,
+            18232,
+            network::Network::Regtest,
+            &ZainodBackend::State {
+                zebrad_cache_dir: zebrad_cache_dir.path().to_path_buf(),
+            },
+        )
+        .unwrap();
+
+        let config =
+            std::fs::read_to_string(config_dir.path().join(super::ZAINOD_FILENAME)).unwrap();
+        assert!(config.contains("\nbackend = \"state\"\n"));
+        assert!(config.contains(&format!("\ndb_path = \"{zebrad_cache_path}\"\n")));
+    }
+
+    #[test]
+    fn zainod_json_rpc_server() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        super::zainod(
+            config_dir.path(),
+            cache_dir.path().to_path_buf(),
+            1234,
+            Some(1235),
+            18232,
+            network::Network::Regtest,
+            &ZainodBackend::Fetch,
+        )
+        .unwrap();
+
+        let config =
+            std::fs::read_to_string(config_dir.path().join(super::ZAINOD_FILENAME)).unwrap();
+        assert!(config.contains(
+            "\nenable_json_server = true\n\n# JsonRPC server listen addr.\njson_rpc_listen_address = \"localhost:1235\"\n"
+        ));
+    }
+
     #[test]
     fn lightwalletd() {
         let config_dir = tempfile::tempdir().unwrap();
@@ -584,7 +874,7 @@ no_db = true
             config_dir.path(),
             1234,
             log_file_path.clone(),
-            PathBuf::from("conf_path"),
+            Some(PathBuf::from("conf_path")),
         )
         .unwrap();
         let log_file_path = log_file_path.to_str().unwrap();
@@ -601,4 +891,54 @@ zcash-conf-path: conf_path"
             )
         )
     }
+
+    #[test]
+    fn lightwalletd_darkside() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let logs_dir = tempfile::tempdir().unwrap();
+        let log_file_path = logs_dir.path().join(logs::LIGHTWALLETD_LOG);
+
+        super::lightwalletd(config_dir.path(), 1234, log_file_path.clone(), None).unwrap();
+        let log_file_path = log_file_path.to_str().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(config_dir.path().join(super::LIGHTWALLETD_FILENAME)).unwrap(),
+            format!(
+                "\
+grpc-bind-addr: 127.0.0.1:1234
+cache-size: 10
+log-file: {log_file_path}
+log-level: 10"
+            )
+        )
+    }
+
+    #[test]
+    fn zcashd_conf_network() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let network = |network| {
+            let config_path = super::zcashd(
+                config_dir.path(),
+                1234,
+                &network::ActivationHeights::default(),
+                None,
+                network,
+            )
+            .unwrap();
+            super::zcashd_conf_network(&config_path).unwrap()
+        };
+
+        assert!(matches!(
+            network(network::Network::Regtest),
+            network::Network::Regtest
+        ));
+        assert!(matches!(
+            network(network::Network::Testnet),
+            network::Network::Testnet
+        ));
+        assert!(matches!(
+            network(network::Network::Mainnet),
+            network::Network::Mainnet
+        ));
+    }
 }
@@ -1,6 +1,10 @@
 //! Crate level error module
 
+use std::sync::Arc;
+
 /// Errors associated with launching processes
+///
+/// Sources which are not `Clone` are wrapped in an [`Arc`] so the error can be cloned.
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum LaunchError {
     /// Process failed during launch
@@ -17,4 +21,277 @@ pub enum LaunchError {
         /// Stderr log
         stderr: String,
     },
+    /// Checkpoints are only supported on regtest, where the chain state is a copy of the chain cache. On other
+    /// networks the process runs directly from the chain cache, which must not be modified.
+    #[error("checkpoints are not supported on {0}")]
+    CheckpointUnsupported(crate::network::Network),
+    /// Checkpoint snapshot could not be taken or restored
+    #[error("failed to snapshot or restore checkpoint")]
+    Checkpoint(#[source] Arc<std::io::Error>),
+    /// Zcashd config file was not specified for a process which requires it, i.e. Lightwalletd outside darkside mode
+    #[error("zcashd config must be specified")]
+    MissingZcashdConf,
+    /// Zcashd config file could not be read
+    #[error("failed to read zcashd config")]
+    ZcashdConf(#[source] Arc<std::io::Error>),
+    /// State cache could not be loaded
+    #[error("failed to load state cache")]
+    StateCache(#[source] Arc<StateCacheError>),
+    /// Fault-injecting proxy failed to bind its listen port
+    #[error("failed to launch proxy")]
+    Proxy(#[source] Arc<std::io::Error>),
+    /// Process could not be spawned, i.e. the binary was not found
+    #[error("failed to spawn {process_name}")]
+    Spawn {
+        /// Process name
+        process_name: String,
+        /// Spawn error
+        #[source]
+        source: Arc<std::io::Error>,
+    },
+    /// Chain cache was not specified for a network which requires it, i.e. any network other than regtest
+    #[error("chain cache must be specified when not using a regtest network, found {0}")]
+    MissingChainCache(crate::network::Network),
+    /// Genesis block could not be generated after launching the validator
+    #[error("failed to generate genesis block")]
+    GenesisBlock(#[source] Arc<GenerateBlocksError>),
+    /// Fixed port specified in the config is already in use
+    #[error("fixed port {0} is not free")]
+    PortUnavailable(portpicker::Port),
+    /// No unused port could be found
+    #[error("no ports free")]
+    NoFreePort,
+    /// Config file could not be written
+    #[error("failed to write config")]
+    Config(#[source] Arc<std::io::Error>),
+}
+
+impl From<StateCacheError> for LaunchError {
+    fn from(error: StateCacheError) -> Self {
+        LaunchError::StateCache(Arc::new(error))
+    }
+}
+
+/// Errors associated with validator JSON-RPC requests
+#[derive(thiserror::Error, Debug)]
+pub enum RpcError {
+    /// Request failed to reach the RPC server or the response could not be read
+    #[error("RPC request failed")]
+    Transport(#[from] reqwest::Error),
+    /// RPC server responded with an unsuccessful HTTP status and no JSON-RPC response
+    #[error("RPC server responded with HTTP status {status}: {body}")]
+    HttpStatus {
+        /// HTTP status code
+        status: u16,
+        /// Response body
+        body: String,
+    },
+    /// RPC server responded with an error object
+    #[error("RPC error {code}: {message}")]
+    Response {
+        /// JSON-RPC error code
+        code: i64,
+        /// Error message
+        message: String,
+    },
+    /// Response could not be decoded
+    #[error("invalid RPC response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Errors associated with waiting for a validator or indexer to reach an expected state
+#[derive(thiserror::Error, Debug)]
+pub enum WaitError {
+    /// Expected state was not reached before the timeout
+    #[error("timed out after {timeout:?} waiting for {waiting_for}")]
+    Timeout {
+        /// Timeout duration
+        timeout: std::time::Duration,
+        /// Description of the expected state
+        waiting_for: String,
+    },
+    /// Validator JSON-RPC request failed
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    /// Failed to connect to the indexer gRPC server
+    #[error("failed to connect to indexer")]
+    Connection(#[from] tonic::transport::Error),
+    /// Indexer gRPC request failed
+    #[error("indexer gRPC request failed")]
+    Grpc(#[from] tonic::Status),
+    /// No indexer process is running, i.e. [`crate::indexer::Empty`]
+    #[error("no indexer process is running")]
+    NoIndexer,
+}
+
+/// Errors associated with indexer health checks
+#[derive(thiserror::Error, Debug)]
+pub enum HealthError {
+    /// Failed to connect to the indexer gRPC server
+    #[error("failed to connect to indexer")]
+    Connection(#[from] tonic::transport::Error),
+    /// Indexer gRPC request failed
+    #[error("indexer gRPC request failed")]
+    Grpc(#[from] tonic::Status),
+}
+
+/// Errors associated with caching and loading indexer state
+#[derive(thiserror::Error, Debug)]
+pub enum StateCacheError {
+    /// Indexer has no state to cache
+    #[error("state caching is not supported by {0}")]
+    Unsupported(&'static str),
+    /// State cache path already exists
+    #[error("state cache already exists: {0}")]
+    AlreadyExists(std::path::PathBuf),
+    /// Failed to read or write the state cache
+    #[error("failed to read or write state cache")]
+    Io(#[from] std::io::Error),
+    /// State cache metadata could not be decoded
+    #[error("invalid state cache metadata: {0}")]
+    InvalidMetadata(String),
+    /// State cache was created on a different network
+    #[error("state cache network {cached} does not match indexer network {expected}")]
+    NetworkMismatch {
+        /// Network of the cached state
+        cached: String,
+        /// Network of the indexer loading the cache
+        expected: String,
+    },
+    /// Chain tip of the cached state is not in the validator's chain
+    #[error("state cache chain tip {hash} at height {height} is not in the validator's chain")]
+    ChainMismatch {
+        /// Chain height of the cached state
+        height: u32,
+        /// Block hash of the cached chain tip
+        hash: String,
+    },
+    /// Failed to connect to the indexer gRPC server
+    #[error("failed to connect to indexer")]
+    Connection(#[from] tonic::transport::Error),
+    /// Indexer gRPC request failed
+    #[error("indexer gRPC request failed")]
+    Grpc(#[from] tonic::Status),
+    /// Validator JSON-RPC request failed
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+}
+
+/// Errors associated with generating blocks
+#[derive(thiserror::Error, Debug)]
+pub enum GenerateBlocksError {
+    /// Validator failed to provide a block template
+    #[error("failed to get block template: {0}")]
+    BlockTemplate(RpcError),
+    /// Block template could not be decoded or built into a block
+    #[error("invalid block template: {0}")]
+    InvalidBlockTemplate(String),
+    /// Validator did not accept the submitted block
+    #[error("block submission failed")]
+    SubmitBlock(#[from] SubmitBlockError),
+    /// Block could not be built with the requested transactions
+    #[error("failed to select block transactions: {0}")]
+    TransactionSelection(String),
+    /// Block generation condition was not met within the maximum number of blocks
+    #[error("condition not met after generating {0} blocks")]
+    MaxBlocks(u32),
+    /// Requested block timestamp is out of range
+    #[error("invalid block timestamp: {0}")]
+    InvalidTimestamp(String),
+    /// Requested transactions did not arrive in the mempool
+    #[error(transparent)]
+    Mempool(#[from] WaitError),
+    /// Validator JSON-RPC request failed
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    /// Indexer did not sync to the generated blocks
+    #[error("indexer failed to sync: {0}")]
+    IndexerSync(WaitError),
+}
+
+/// Reasons a validator did not accept a block, decoded from the `submitblock` RPC result (BIP 22)
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SubmitBlockError {
+    /// Block is already in the chain
+    #[error("duplicate")]
+    Duplicate,
+    /// Block was previously submitted and found to be invalid
+    #[error("duplicate-invalid")]
+    DuplicateInvalid,
+    /// Block was previously submitted but is not part of the best chain
+    #[error("duplicate-inconclusive")]
+    DuplicateInconclusive,
+    /// Block is valid but is not part of the best chain
+    #[error("inconclusive")]
+    Inconclusive,
+    /// Block was rejected
+    #[error("rejected: {reason}")]
+    Rejected {
+        /// Rejection reason reported by the validator
+        reason: String,
+    },
+}
+
+/// Errors associated with funding addresses from the validator
+#[derive(thiserror::Error, Debug)]
+pub enum FundError {
+    /// Address is not valid for the validator network
+    #[error("invalid address: {0}")]
+    InvalidAddress(String),
+    /// Address has no receiver for the requested pool
+    #[error("address {address} has no {pool:?} receiver")]
+    NoReceiver {
+        /// Address to fund
+        address: String,
+        /// Requested pool
+        pool: zcash_protocol::PoolType,
+    },
+    /// Validator cannot fund the requested pool
+    #[error("funding the {0:?} pool is not supported by this validator")]
+    UnsupportedPool(zcash_protocol::PoolType),
+    /// Funding source does not hold enough funds for the requested amount and fee
+    #[error("insufficient funds: {available} zatoshis available, {required} zatoshis required")]
+    InsufficientFunds {
+        /// Zatoshis available to the funding source
+        available: u64,
+        /// Zatoshis required, including the fee
+        required: u64,
+    },
+    /// Validator wallet operation failed
+    #[error("wallet operation failed: {0}")]
+    Operation(String),
+    /// Funding transaction could not be built
+    #[error("failed to build funding transaction: {0}")]
+    Transaction(String),
+    /// Funding transaction was not confirmed
+    #[error(transparent)]
+    GenerateBlocks(#[from] GenerateBlocksError),
+    /// Wallet operation or funding transaction did not complete in time
+    #[error(transparent)]
+    Wait(#[from] WaitError),
+    /// Validator JSON-RPC request failed
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+}
+
+/// Errors associated with process HTTP endpoints, such as the Zebrad metrics and tracing endpoints
+#[derive(thiserror::Error, Debug)]
+pub enum EndpointError {
+    /// Endpoint was not enabled when the process was launched
+    #[error("{0} endpoint is not enabled")]
+    Disabled(&'static str),
+    /// Request failed to reach the endpoint or the response could not be read
+    #[error("endpoint request failed")]
+    Transport(#[from] reqwest::Error),
+    /// Endpoint responded with an unsuccessful HTTP status
+    #[error("endpoint responded with HTTP status {status}: {body}")]
+    HttpStatus {
+        /// HTTP status code
+        status: u16,
+        /// Response body
+        body: String,
+    },
+    /// Response could not be decoded
+    #[error("invalid endpoint response: {0}")]
+    InvalidResponse(String),
 }
@@ -0,0 +1,195 @@
+//! Module for funding addresses under test from validator mining rewards, without a light client.
+
+use zcash_protocol::{
+    consensus::BlockHeight,
+    value::{Zatoshis, COIN},
+    PoolType, ShieldedProtocol,
+};
+
+use crate::{
+    error::{FundError, RpcError},
+    network::ActivationHeights,
+    rpc::RpcClient,
+};
+
+#[cfg(feature = "zebrad-funding")]
+pub(crate) mod zebrad;
+
+/// Number of confirmations required before a coinbase output can be spent.
+pub const COINBASE_MATURITY: u32 = 100;
+
+/// Returns the chain height at which the first coinbase output paid to the `pool` is spendable by a wallet.
+///
+/// Coinbase outputs can only be paid to the Sapling pool from Heartwood activation and to the Orchard pool from NU5
+/// activation. Blocks are numbered from the genesis block at height 0, which cannot be spent from.
+pub fn coinbase_spendable_height(
+    activation_heights: &ActivationHeights,
+    pool: PoolType,
+) -> BlockHeight {
+    let first_coinbase_height = match pool {
+        PoolType::Transparent => BlockHeight::from_u32(1),
+        PoolType::Shielded(ShieldedProtocol::Sapling) => activation_heights.heartwood,
+        PoolType::Shielded(ShieldedProtocol::Orchard) => activation_heights.nu5,
+    }
+    .max(BlockHeight::from_u32(1));
+
+    first_coinbase_height + COINBASE_MATURITY
+}
+
+/// Formats `amount` as an exact decimal ZEC amount, as accepted by the validator wallet RPCs.
+pub(crate) fn zec_amount(amount: Zatoshis) -> String {
+    let zatoshis = amount.into_u64();
+
+    format!("{}.{:08}", zatoshis / COIN, zatoshis % COIN)
+}
+
+/// Returns the receiver of `address` for the given `pool`, encoded as an address the validator can send to.
+///
+/// Unified addresses are split into their receivers with the `z_listunifiedreceivers` RPC.
+pub(crate) async fn receiver_for_pool(
+    rpc_client: &RpcClient,
+    address: &str,
+    pool: PoolType,
+) -> Result<String, FundError> {
+    let address_type = address_type(rpc_client, address).await?;
+    let no_receiver = || FundError::NoReceiver {
+        address: address.to_string(),
+        pool,
+    };
+
+    if address_type == "unified" {
+        let receivers = rpc_client
+            .call("z_listunifiedreceivers", serde_json::json!([address]))
+            .await?;
+        receiver_types(pool)
+            .iter()
+            .find_map(|receiver_type| receivers.get(receiver_type).and_then(|r| r.as_str()))
+            .map(|receiver| receiver.to_string())
+            .ok_or_else(no_receiver)
+    } else if receiver_types(pool).contains(&address_type.as_str()) {
+        Ok(address.to_string())
+    } else {
+        Err(no_receiver())
+    }
+}
+
+/// Returns the pool that coinbase outputs paid to `address` are received in.
+///
+/// Unified addresses are assumed to receive coinbase outputs in the most recent pool they have a receiver for.
+pub(crate) async fn coinbase_pool(
+    rpc_client: &RpcClient,
+    address: &str,
+) -> Result<PoolType, FundError> {
+    let address_type = address_type(rpc_client, address).await?;
+    let address_receiver_types: Vec<String> = if address_type == "unified" {
+        let receivers = rpc_client
+            .call("z_listunifiedreceivers", serde_json::json!([address]))
+            .await?;
+        receivers
+            .as_object()
+            .ok_or_else(|| RpcError::InvalidResponse(receivers.to_string()))?
+            .iter()
+            .filter(|(_, receiver)| receiver.is_string())
+            .map(|(receiver_type, _)| receiver_type.clone())
+            .collect()
+    } else {
+        vec![address_type]
+    };
+
+    [
+        PoolType::Shielded(ShieldedProtocol::Orchard),
+        PoolType::Shielded(ShieldedProtocol::Sapling),
+        PoolType::Transparent,
+    ]
+    .into_iter()
+    .find(|pool| {
+        receiver_types(*pool)
+            .iter()
+            .any(|receiver_type| address_receiver_types.iter().any(|t| t == receiver_type))
+    })
+    .ok_or_else(|| FundError::InvalidAddress(address.to_string()))
+}
+
+/// Validates `address` with the `z_validateaddress` RPC and returns the address type.
+async fn address_type(rpc_client: &RpcClient, address: &str) -> Result<String, FundError> {
+    let validation = rpc_client
+        .call("z_validateaddress", serde_json::json!([address]))
+        .await?;
+    if validation.get("isvalid").and_then(|valid| valid.as_bool()) != Some(true) {
+        return Err(FundError::InvalidAddress(address.to_string()));
+    }
+
+    validation
+        .get("address_type")
+        .and_then(|address_type| address_type.as_str())
+        .map(|address_type| address_type.to_string())
+        .ok_or_else(|| RpcError::InvalidResponse(validation.to_string()).into())
+}
+
+/// Returns the address and unified receiver types of the `pool`, as reported by the validator RPCs.
+fn receiver_types(pool: PoolType) -> &'static [&'static str] {
+    match pool {
+        PoolType::Transparent => &["p2pkh", "p2sh"],
+        PoolType::Shielded(ShieldedProtocol::Sapling) => &["sapling"],
+        PoolType::Shielded(ShieldedProtocol::Orchard) => &["orchard"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zcash_protocol::{consensus::BlockHeight, value::Zatoshis, PoolType, ShieldedProtocol};
+
+    use crate::network::ActivationHeights;
+
+    #[test]
+    fn coinbase_spendable_height() {
+        let activation_heights = ActivationHeights {
+            overwinter: 1.into(),
+            sapling: 1.into(),
+            blossom: 1.into(),
+            heartwood: 5.into(),
+            canopy: 5.into(),
+            nu5: 10.into(),
+            nu6: 10.into(),
+        };
+
+        assert_eq!(
+            super::coinbase_spendable_height(&activation_heights, PoolType::Transparent),
+            BlockHeight::from_u32(101)
+        );
+        assert_eq!(
+            super::coinbase_spendable_height(
+                &activation_heights,
+                PoolType::Shielded(ShieldedProtocol::Sapling)
+            ),
+            BlockHeight::from_u32(105)
+        );
+        assert_eq!(
+            super::coinbase_spendable_height(
+                &activation_heights,
+                PoolType::Shielded(ShieldedProtocol::Orchard)
+            ),
+            BlockHeight::from_u32(110)
+        );
+        assert_eq!(
+            super::coinbase_spendable_height(
+                &ActivationHeights::default(),
+                PoolType::Shielded(ShieldedProtocol::Orchard)
+            ),
+            BlockHeight::from_u32(101)
+        );
+    }
+
+    #[test]
+    fn zec_amount() {
+        assert_eq!(super::zec_amount(Zatoshis::ZERO), "0.00000000");
+        assert_eq!(
+            super::zec_amount(Zatoshis::from_u64(100_000).unwrap()),
+            "0.00100000"
+        );
+        assert_eq!(
+            super::zec_amount(Zatoshis::from_u64(1_234_567_890_123_456).unwrap()),
+            "12345678.90123456"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Funding from Zebrad coinbase outputs, spent to the recipient with transactions built by this crate.
+//!
+//! Zebrad coinbase outputs are paid to a services-managed transparent key and may only be spent to shielded outputs.
+//! Only Orchard outputs are created, but the transaction builder requires Sapling provers so the mock provers of
+//! `sapling-crypto` are used. This module is only available with the `zebrad-funding` feature.
+
+use rand::rngs::OsRng;
+use ripemd::Ripemd160;
+use sha2::{Digest as _, Sha256};
+use zcash_address::unified::{self, Container as _, Encoding as _};
+use zcash_primitives::{
+    legacy::{Script, TransparentAddress},
+    transaction::{
+        builder::{BuildConfig, Builder},
+        components::{amount::NonNegativeAmount, OutPoint, TxOut},
+        fees::zip317::{FeeError, FeeRule},
+        TxId,
+    },
+};
+use zcash_protocol::{consensus::BlockHeight, memo::MemoBytes, value::Zatoshis};
+
+use crate::{
+    error::FundError,
+    network::{ActivationHeights, RegtestParameters},
+};
+
+/// Secret key of the services-managed transparent address that Zebrad funding coinbase outputs are paid to.
+const FUNDING_SECRET_KEY: [u8; 32] = [0x01; 32];
+
+/// Orchard spending key of the services-managed address that receives the change of Zebrad funding transactions.
+const FUNDING_ORCHARD_SPENDING_KEY: [u8; 32] = [0x02; 32];
+
+/// Returns the script of the services-managed transparent address that Zebrad funding coinbase outputs are paid to.
+pub(crate) fn funding_script() -> Script {
+    let public_key = funding_secret_key()
+        .public_key(&secp256k1::Secp256k1::signing_only())
+        .serialize();
+    let public_key_hash: [u8; 20] = Ripemd160::digest(Sha256::digest(public_key)).into();
+
+    TransparentAddress::PublicKeyHash(public_key_hash).script()
+}
+
+/// Builds a transaction spending the funding coinbase output `coin` at `outpoint` to the Orchard `receiver`.
+///
+/// Coinbase outputs may only be spent to shielded outputs, so the change is sent to a services-managed Orchard
+/// address. Returns the txid and the serialized transaction.
+pub(crate) fn build_orchard_spend(
+    activation_heights: ActivationHeights,
+    target_height: BlockHeight,
+    outpoint: OutPoint,
+    coin: TxOut,
+    receiver: &str,
+    amount: Zatoshis,
+) -> Result<(TxId, Vec<u8>), FundError> {
+    let recipient = decode_orchard_receiver(receiver)?;
+    let change_address = {
+        let spending_key = Option::from(orchard::keys::SpendingKey::from_bytes(
+            FUNDING_ORCHARD_SPENDING_KEY,
+        ))
+        .expect("should be a valid spending key");
+        orchard::keys::FullViewingKey::from(&spending_key)
+            .address_at(0u32, orchard::keys::Scope::Internal)
+    };
+
+    let builder = |change: u64| -> Result<Builder<'static, RegtestParameters, ()>, FundError> {
+        let mut builder = Builder::new(
+            RegtestParameters(activation_heights),
+            target_height,
+            BuildConfig::Standard {
+                sapling_anchor: None,
+                orchard_anchor: Some(orchard::Anchor::empty_tree()),
+            },
+        );
+        builder
+            .add_transparent_input(funding_secret_key(), outpoint.clone(), coin.clone())
+            .map_err(|e| FundError::Transaction(e.to_string()))?;
+        builder
+            .add_orchard_output::<FeeError>(None, recipient, amount.into_u64(), MemoBytes::empty())
+            .map_err(|e| FundError::Transaction(e.to_string()))?;
+        builder
+            .add_orchard_output::<FeeError>(None, change_address, change, MemoBytes::empty())
+            .map_err(|e| FundError::Transaction(e.to_string()))?;
+
+        Ok(builder)
+    };
+
+    // the fee only depends on the number of inputs and outputs so it is calculated with a placeholder change value
+    let fee_rule = FeeRule::standard();
+    let fee = builder(0)?
+        .get_fee(&fee_rule)
+        .map_err(|e| FundError::Transaction(e.to_string()))?;
+    let available = coin.value.into_u64();
+    let required = amount.into_u64() + fee.into_u64();
+    if available < required {
+        return Err(FundError::InsufficientFunds {
+            available,
+            required,
+        });
+    }
+
+    let build_result = builder(available - required)?
+        .build(
+            OsRng,
+            &sapling_crypto::prover::mock::MockSpendProver,
+            &sapling_crypto::prover::mock::MockOutputProver,
+            &fee_rule,
+        )
+        .map_err(|e| FundError::Transaction(e.to_string()))?;
+    let transaction = build_result.transaction();
+    let mut transaction_data = Vec::new();
+    transaction
+        .write(&mut transaction_data)
+        .map_err(|e| FundError::Transaction(e.to_string()))?;
+
+    Ok((transaction.txid(), transaction_data))
+}
+
+/// Returns the coinbase output paying `value` zatoshis to the services-managed funding address.
+pub(crate) fn funding_coin(value: u64) -> Result<TxOut, FundError> {
+    Ok(TxOut {
+        value: NonNegativeAmount::from_u64(value)
+            .map_err(|_| FundError::Transaction(format!("invalid coinbase value: {value}")))?,
+        script_pubkey: funding_script(),
+    })
+}
+
+fn funding_secret_key() -> secp256k1::SecretKey {
+    secp256k1::SecretKey::from_slice(&FUNDING_SECRET_KEY).expect("should be a valid secret key")
+}
+
+/// Decodes the Orchard receiver from a unified address, as returned by `z_listunifiedreceivers`.
+fn decode_orchard_receiver(receiver: &str) -> Result<orchard::Address, FundError> {
+    let invalid_address = || FundError::InvalidAddress(receiver.to_string());
+
+    let (_, unified_address) = unified::Address::decode(receiver).map_err(|_| invalid_address())?;
+    let receiver_bytes = unified_address
+        .items()
+        .into_iter()
+        .find_map(|item| match item {
+            unified::Receiver::Orchard(bytes) => Some(bytes),
+            _ => None,
+        })
+        .ok_or_else(invalid_address)?;
+
+    Option::from(orchard::Address::from_raw_address_bytes(&receiver_bytes))
+        .ok_or_else(invalid_address)
+}
@@ -3,75 +3,280 @@
 //! Processes which are not strictly indexers but have a similar role in serving light-clients/light-wallets
 //! (i.e. Lightwalletd) are also included in this category and are referred to as "light-nodes".
 
-use std::{fs::File, path::PathBuf, process::Child};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    process::Child,
+    sync::Arc,
+    time::Duration,
+};
 
 use getset::{CopyGetters, Getters};
 use portpicker::Port;
 use tempfile::TempDir;
+use zcash_client_backend::proto::{
+    compact_formats::CompactTx,
+    service::{
+        compact_tx_streamer_client::CompactTxStreamerClient, ChainSpec, Empty as EmptyRequest,
+        Exclude,
+    },
+};
+use zcash_primitives::transaction::TxId;
+use zcash_protocol::consensus::BlockHeight;
 
 use crate::{
+    block,
+    checkpoint::{self, Checkpoint},
     config,
-    error::LaunchError,
-    launch, logs,
+    error::{HealthError, LaunchError, RpcError, StateCacheError, WaitError},
+    launch, logs, mempool,
     network::{self, Network},
+    rpc::RpcClient,
     Process,
 };
 
+/// State cache metadata filename, see [`Indexer::cache_state`].
+pub const STATE_CACHE_METADATA_FILENAME: &str = "state_cache.json";
+
 /// Zainod configuration
 ///
 /// If `listen_port` is `None`, a port is picked at random between 15000-25000.
 ///
+/// If `enable_json_rpc` is `true`, Zainod also serves a zcashd-compatible JSON-RPC interface. If `json_rpc_listen_port`
+/// is `None`, a port is picked at random between 15000-25000. See [`Zainod::json_rpc_port`].
+///
 /// The `validator_port` must be specified and the validator process must be running before launching Zainod.
 ///
 /// `network` must match the configured network of the validator.
+///
+/// If `chain_cache` is `Some`, the state cache created by [`Indexer::cache_state`] is validated against the validator
+/// chain and loaded on launch. For `Regtest`, the cache is copied so the original cache is not modified.
+///
+/// See [`ZainodBackend`] for selecting how Zainod reads chain data from the validator.
 pub struct ZainodConfig {
     /// Zainod binary location
     pub zainod_bin: Option<PathBuf>,
     /// Listen RPC port
     pub listen_port: Option<Port>,
+    /// Enable JSON-RPC server
+    pub enable_json_rpc: bool,
+    /// Listen JSON-RPC port
+    pub json_rpc_listen_port: Option<Port>,
     /// Validator RPC port
     pub validator_port: Port,
-    /// Chain cache path
+    /// State cache path
     pub chain_cache: Option<PathBuf>,
     /// Network type.
     pub network: Network,
+    /// Zainod backend
+    pub backend: ZainodBackend,
+}
+
+/// Selects how Zainod reads chain data from the validator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ZainodBackend {
+    /// Fetch chain data from the validator's JSON-RPC server (FetchService)
+    Fetch,
+    /// Read chain data directly from Zebrad's state database (StateService). Not supported by Zcashd.
+    ///
+    /// The validator's JSON-RPC server is still used for requests not served by the state, such as sending
+    /// transactions, so `validator_port` must also be specified.
+    State {
+        /// Zebrad state cache directory, see [`crate::validator::Validator::chain_dir`]
+        zebrad_cache_dir: PathBuf,
+    },
 }
 
 /// Lightwalletd configuration
 ///
 /// If `listen_port` is `None`, a port is picked at random between 15000-25000.
 ///
-/// The `zcash_conf` path must be specified and the validator process must be running before launching Lightwalletd.
+/// The `zcashd_conf` path must be specified and the validator process must be running before launching Lightwalletd.
 /// When running a validator that is not Zcashd (i.e. Zebrad), a zcash config file must still be created to specify the
 /// validator port. This is automatically handled by [`crate::LocalNet::launch`] when using [`crate::LocalNet`].
+///
+/// The network type is read from the `zcashd_conf` file.
+///
+/// If `darkside` is `true`, Lightwalletd is launched in darkside mode, serving a chain staged over the
+/// `DarksideStreamer` gRPC service instead of connecting to a validator. No validator is required and `zcashd_conf`
+/// is ignored. The darkside chain is treated as regtest.
 pub struct LightwalletdConfig {
     /// Lightwalletd binary location
     pub lightwalletd_bin: Option<PathBuf>,
     /// Listen RPC port
     pub listen_port: Option<Port>,
     /// Zcashd configuration file location. Required even when running non-Zcashd validators.
-    pub zcashd_conf: PathBuf,
+    pub zcashd_conf: Option<PathBuf>,
+    /// Launch in darkside mode
+    pub darkside: bool,
 }
 
 /// Empty configuration
 ///
 /// For use when not launching an Indexer with [`crate::LocalNet::launch`].
+#[derive(Default)]
 pub struct EmptyConfig {}
 
+impl Default for ZainodConfig {
+    fn default() -> Self {
+        Self {
+            zainod_bin: None,
+            listen_port: None,
+            enable_json_rpc: false,
+            json_rpc_listen_port: None,
+            validator_port: 0,
+            chain_cache: None,
+            network: Network::Regtest,
+            backend: ZainodBackend::Fetch,
+        }
+    }
+}
+
+impl Default for LightwalletdConfig {
+    fn default() -> Self {
+        Self {
+            lightwalletd_bin: None,
+            listen_port: None,
+            zcashd_conf: None,
+            darkside: false,
+        }
+    }
+}
+
+/// Validator connection info, used by [`crate::LocalNet`] to connect indexers to the validator or proxy.
+///
+/// See [`ConnectValidator`].
+#[derive(Clone, Debug, Getters, CopyGetters)]
+#[getset(get = "pub")]
+pub struct ValidatorConnection {
+    /// Validator RPC port
+    #[getset(skip)]
+    #[getset(get_copy = "pub")]
+    rpc_port: Port,
+    /// Zcashd configuration file location, specifying the RPC port
+    zcashd_conf: PathBuf,
+    /// Validator chain state directory, see [`crate::validator::Validator::chain_dir`]
+    chain_dir: PathBuf,
+}
+
+impl ValidatorConnection {
+    /// Creates a new validator connection.
+    pub fn new(rpc_port: Port, zcashd_conf: PathBuf, chain_dir: PathBuf) -> Self {
+        ValidatorConnection {
+            rpc_port,
+            zcashd_conf,
+            chain_dir,
+        }
+    }
+}
+
+/// Functionality for indexer configs to consume validator connection info.
+///
+/// Allows [`crate::LocalNet::launch`] to connect any indexer to any validator.
+pub trait ConnectValidator {
+    /// Overwrites the validator connection fields of the config to match the `connection`.
+    fn connect(&mut self, connection: &ValidatorConnection);
+}
+
+impl ConnectValidator for ZainodConfig {
+    /// Overwrites `validator_port`. When using [`ZainodBackend::State`], the `zebrad_cache_dir` is also overwritten to
+    /// match the validator's chain state directory.
+    fn connect(&mut self, connection: &ValidatorConnection) {
+        self.validator_port = connection.rpc_port;
+        if let ZainodBackend::State { zebrad_cache_dir } = &mut self.backend {
+            *zebrad_cache_dir = connection.chain_dir.clone();
+        }
+    }
+}
+
+impl ConnectValidator for LightwalletdConfig {
+    /// Overwrites `zcashd_conf`.
+    fn connect(&mut self, connection: &ValidatorConnection) {
+        self.zcashd_conf = Some(connection.zcashd_conf.clone());
+    }
+}
+
+impl ConnectValidator for EmptyConfig {
+    fn connect(&mut self, _connection: &ValidatorConnection) {}
+}
+
+impl ConnectValidator for AnyIndexerConfig {
+    fn connect(&mut self, connection: &ValidatorConnection) {
+        match self {
+            AnyIndexerConfig::Zainod(config) => config.connect(connection),
+            AnyIndexerConfig::Lightwalletd(config) => config.connect(connection),
+        }
+    }
+}
+
+/// Functionality for overriding the fields common to all indexer configs, see [`crate::LocalNetBuilder`].
+pub trait IndexerConfig: ConnectValidator + Default + Send {
+    /// Sets the indexer binary location.
+    fn set_bin(&mut self, bin: PathBuf);
+}
+
+impl IndexerConfig for ZainodConfig {
+    fn set_bin(&mut self, bin: PathBuf) {
+        self.zainod_bin = Some(bin);
+    }
+}
+
+impl IndexerConfig for LightwalletdConfig {
+    fn set_bin(&mut self, bin: PathBuf) {
+        self.lightwalletd_bin = Some(bin);
+    }
+}
+
+impl IndexerConfig for EmptyConfig {
+    /// Empty has no binary so this does nothing.
+    fn set_bin(&mut self, _bin: PathBuf) {}
+}
+
+impl IndexerConfig for AnyIndexerConfig {
+    fn set_bin(&mut self, bin: PathBuf) {
+        match self {
+            AnyIndexerConfig::Zainod(config) => config.set_bin(bin),
+            AnyIndexerConfig::Lightwalletd(config) => config.set_bin(bin),
+        }
+    }
+}
+
 /// Functionality for indexer/light-node processes.
-pub trait Indexer: Sized {
+pub trait Indexer: Sized + Send + Sync {
     /// Config filename
     const CONFIG_FILENAME: &str;
 
     /// Indexer config struct
-    type Config;
+    type Config: IndexerConfig;
 
     /// Launch the process.
-    fn launch(config: Self::Config) -> Result<Self, LaunchError>;
+    fn launch(
+        config: Self::Config,
+    ) -> impl std::future::Future<Output = Result<Self, LaunchError>> + Send;
 
     /// Stop the process.
     fn stop(&mut self);
 
+    /// Stops the process, snapshots the indexer state and restarts the process.
+    ///
+    /// Used alongside [`crate::validator::Validator::checkpoint`] to restore an attached indexer to the same state as
+    /// the validator. See [`crate::LocalNet::checkpoint`].
+    fn checkpoint(&mut self) -> Result<Checkpoint, LaunchError>;
+
+    /// Stops the process, restores the indexer state from the `checkpoint` and restarts the process.
+    fn rollback(&mut self, checkpoint: &Checkpoint) -> Result<(), LaunchError>;
+
+    /// Caches the indexer state to `state_cache`, along with the validator chain tip it is synced to. This stops the
+    /// process.
+    ///
+    /// The cache is loaded by passing it as the chain cache of the indexer config and is validated against the
+    /// validator chain on launch. Returns [`crate::error::StateCacheError::Unsupported`] if the indexer has no state
+    /// to cache.
+    fn cache_state(
+        &mut self,
+        state_cache: PathBuf,
+    ) -> impl std::future::Future<Output = Result<(), StateCacheError>> + Send;
+
     /// Get temporary config directory.
     fn config_dir(&self) -> &TempDir;
 
@@ -83,6 +288,38 @@ pub trait Indexer: Sized {
         self.config_dir().path().join(Self::CONFIG_FILENAME)
     }
 
+    /// Returns the port of the light client gRPC server, `None` if there is no indexer process i.e. [`Empty`].
+    fn grpc_port(&self) -> Option<Port>;
+
+    /// Returns the URI of the light client gRPC server, `None` if there is no indexer process i.e. [`Empty`].
+    fn grpc_uri(&self) -> Option<http::Uri> {
+        self.grpc_port().map(network::localhost_uri)
+    }
+
+    /// Returns the network type, `None` if there is no indexer process i.e. [`Empty`].
+    fn network(&self) -> Option<Network>;
+
+    /// Checks the indexer is serving light client gRPC requests by calling `GetLightdInfo`.
+    fn health_check(&self) -> impl std::future::Future<Output = Result<(), HealthError>> + Send;
+
+    /// Waits until the transaction with `txid` is served by the `GetMempoolTx` gRPC and returns it.
+    ///
+    /// Returns [`crate::error::WaitError::Timeout`] if the transaction has not arrived within `timeout`.
+    fn wait_for_mempool_tx(
+        &self,
+        txid: TxId,
+        timeout: Duration,
+    ) -> impl std::future::Future<Output = Result<CompactTx, WaitError>> + Send;
+
+    /// Waits until the chain tip served by the `GetLatestBlock` gRPC is at least `height`.
+    ///
+    /// Returns [`crate::error::WaitError::Timeout`] if the chain tip has not reached `height` within `timeout`.
+    fn wait_for_height(
+        &self,
+        height: BlockHeight,
+        timeout: Duration,
+    ) -> impl std::future::Future<Output = Result<(), WaitError>> + Send;
+
     /// Prints the stdout log.
     fn print_stdout(&self) {
         let stdout_log_path = self.logs_dir().path().join(logs::STDOUT_LOG);
@@ -106,10 +343,81 @@ pub struct Zainod {
     #[getset(skip)]
     #[getset(get_copy = "pub")]
     port: Port,
+    /// JSON-RPC port, `None` if the JSON-RPC server is not enabled
+    #[getset(skip)]
+    #[getset(get_copy = "pub")]
+    json_rpc_port: Option<Port>,
     /// Logs directory
     logs_dir: TempDir,
     /// Config directory
     config_dir: TempDir,
+    /// Data directory
+    data_dir: TempDir,
+    /// Chain state directory
+    #[getset(skip)]
+    chain_dir: PathBuf,
+    /// Network type
+    #[getset(skip)]
+    network: Network,
+    /// Zainod backend
+    #[getset(skip)]
+    backend: ZainodBackend,
+    /// Validator RPC client, used for validating state caches
+    #[getset(skip)]
+    validator_rpc_client: RpcClient,
+    /// Launch command, kept for restarting the process
+    #[getset(skip)]
+    command: std::process::Command,
+}
+
+impl Zainod {
+    /// Spawns the Zainod process from the launch `command` and waits for the indexer to start.
+    fn spawn(
+        command: &mut std::process::Command,
+        logs_dir: &TempDir,
+    ) -> Result<Child, LaunchError> {
+        let mut handle = command.spawn().map_err(|e| LaunchError::Spawn {
+            process_name: Process::Zainod.to_string(),
+            source: Arc::new(e),
+        })?;
+
+        logs::write_logs(&mut handle, logs_dir);
+        launch::wait(
+            Process::Zainod,
+            &mut handle,
+            logs_dir,
+            None,
+            &["Zaino Indexer started successfully."],
+            &["Error:"],
+            &[],
+        )?;
+
+        Ok(handle)
+    }
+
+    /// Restarts the process after it has been stopped.
+    fn restart(&mut self) -> Result<(), LaunchError> {
+        self.handle = Self::spawn(&mut self.command, &self.logs_dir)?;
+
+        Ok(())
+    }
+
+    /// Loads the `state_cache` into `data_dir`. Returns the path to the loaded state.
+    ///
+    /// If network is not `Regtest` variant, the state cache will not be copied and the original cache path will be
+    /// returned instead
+    fn load_state(
+        state_cache: PathBuf,
+        data_dir: PathBuf,
+        network: Network,
+    ) -> Result<PathBuf, StateCacheError> {
+        if matches!(network, Network::Regtest) {
+            checkpoint::copy_dir_contents(&state_cache, &data_dir)?;
+            Ok(data_dir)
+        } else {
+            Ok(state_cache)
+        }
+    }
 }
 
 impl Indexer for Zainod {
@@ -117,25 +425,35 @@ impl Indexer for Zainod {
 
     type Config = ZainodConfig;
 
-    fn launch(config: Self::Config) -> Result<Self, LaunchError> {
+    async fn launch(config: Self::Config) -> Result<Self, LaunchError> {
         let logs_dir = tempfile::tempdir().unwrap();
         let data_dir = tempfile::tempdir().unwrap();
 
-        let port = network::pick_unused_port(config.listen_port);
+        let port = network::pick_unused_port(config.listen_port)?;
+        let json_rpc_port = config
+            .enable_json_rpc
+            .then(|| network::pick_unused_port(config.json_rpc_listen_port))
+            .transpose()?;
         let config_dir = tempfile::tempdir().unwrap();
 
-        let cache_dir = if let Some(cache) = config.chain_cache.clone() {
-            cache
+        let validator_rpc_client = RpcClient::new(config.validator_port)
+            .with_basic_auth(config::RPC_USER, config::RPC_PASSWORD);
+
+        let chain_dir = if let Some(cache) = config.chain_cache.clone() {
+            validate_state_cache(&cache, config.network, &validator_rpc_client).await?;
+            Self::load_state(cache, data_dir.path().to_path_buf(), config.network)?
         } else {
             data_dir.path().to_path_buf()
         };
 
         let config_file_path = config::zainod(
             config_dir.path(),
-            cache_dir,
+            chain_dir.clone(),
             port,
+            json_rpc_port,
             config.validator_port,
             config.network,
+            &config.backend,
         )
         .unwrap();
 
@@ -151,24 +469,20 @@ impl Indexer for Zainod {
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
 
-        let mut handle = command.spawn().unwrap();
-
-        logs::write_logs(&mut handle, &logs_dir);
-        launch::wait(
-            Process::Zainod,
-            &mut handle,
-            &logs_dir,
-            None,
-            &["Zaino Indexer started successfully."],
-            &["Error:"],
-            &[],
-        )?;
+        let handle = Self::spawn(&mut command, &logs_dir)?;
 
         Ok(Zainod {
             handle,
             port,
+            json_rpc_port,
             logs_dir,
             config_dir,
+            data_dir,
+            chain_dir,
+            network: config.network,
+            backend: config.backend,
+            validator_rpc_client,
+            command,
         })
     }
 
@@ -176,6 +490,89 @@ impl Indexer for Zainod {
         self.handle.kill().expect("zainod couldn't be killed")
     }
 
+    /// Returns [`crate::error::LaunchError::CheckpointUnsupported`] if the network is not `Regtest`, as Zainod
+    /// runs directly from the state cache.
+    fn checkpoint(&mut self) -> Result<Checkpoint, LaunchError> {
+        if !matches!(self.network, Network::Regtest) {
+            return Err(LaunchError::CheckpointUnsupported(self.network));
+        }
+        self.stop();
+        let _ = self.handle.wait();
+        let checkpoint = checkpoint::snapshot(&self.chain_dir);
+        self.restart()?;
+
+        checkpoint.map_err(|e| LaunchError::Checkpoint(Arc::new(e)))
+    }
+
+    fn rollback(&mut self, checkpoint: &Checkpoint) -> Result<(), LaunchError> {
+        if !matches!(self.network, Network::Regtest) {
+            return Err(LaunchError::CheckpointUnsupported(self.network));
+        }
+        self.stop();
+        let _ = self.handle.wait();
+        checkpoint::restore(checkpoint, &self.chain_dir)
+            .map_err(|e| LaunchError::Checkpoint(Arc::new(e)))?;
+        self.restart()
+    }
+
+    /// Only supported with the [`ZainodBackend::Fetch`] backend, as the [`ZainodBackend::State`] backend reads chain
+    /// data directly from the Zebrad state and has no state of its own to cache.
+    async fn cache_state(&mut self, state_cache: PathBuf) -> Result<(), StateCacheError> {
+        if matches!(self.backend, ZainodBackend::State { .. }) {
+            return Err(StateCacheError::Unsupported(
+                "zainod with the state backend",
+            ));
+        }
+        if state_cache.exists() {
+            return Err(StateCacheError::AlreadyExists(state_cache));
+        }
+
+        let channel = tonic::transport::Channel::builder(network::localhost_uri(self.port))
+            .connect()
+            .await?;
+        let latest_block = CompactTxStreamerClient::new(channel)
+            .get_latest_block(ChainSpec {})
+            .await?
+            .into_inner();
+
+        self.stop();
+        let _ = self.handle.wait();
+
+        let output = std::process::Command::new("cp")
+            .arg("-r")
+            .arg(&self.chain_dir)
+            .arg(&state_cache)
+            .output()?;
+        if !output.status.success() {
+            return Err(StateCacheError::Io(std::io::Error::other(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            )));
+        }
+
+        write_state_cache_metadata(
+            &state_cache,
+            self.network,
+            latest_block.height,
+            &block::hash_to_hex(&latest_block.hash),
+        )
+    }
+
+    fn wait_for_mempool_tx(
+        &self,
+        txid: TxId,
+        timeout: Duration,
+    ) -> impl std::future::Future<Output = Result<CompactTx, WaitError>> + Send {
+        wait_for_mempool_tx(self.port, txid, timeout)
+    }
+
+    fn wait_for_height(
+        &self,
+        height: BlockHeight,
+        timeout: Duration,
+    ) -> impl std::future::Future<Output = Result<(), WaitError>> + Send {
+        wait_for_height(self.port, height, timeout)
+    }
+
     fn config_dir(&self) -> &TempDir {
         &self.config_dir
     }
@@ -183,6 +580,18 @@ impl Indexer for Zainod {
     fn logs_dir(&self) -> &TempDir {
         &self.logs_dir
     }
+
+    fn grpc_port(&self) -> Option<Port> {
+        Some(self.port)
+    }
+
+    fn network(&self) -> Option<Network> {
+        Some(self.network)
+    }
+
+    fn health_check(&self) -> impl std::future::Future<Output = Result<(), HealthError>> + Send {
+        health_check(self.port)
+    }
 }
 
 impl Drop for Zainod {
@@ -207,6 +616,12 @@ pub struct Lightwalletd {
     logs_dir: TempDir,
     /// Config directory
     config_dir: TempDir,
+    /// Network type
+    #[getset(skip)]
+    network: Network,
+    /// Launch command, kept for restarting the process
+    #[getset(skip)]
+    command: std::process::Command,
 }
 
 impl Lightwalletd {
@@ -215,6 +630,41 @@ impl Lightwalletd {
         let stdout_log_path = self.logs_dir.path().join(logs::LIGHTWALLETD_LOG);
         logs::print_log(stdout_log_path);
     }
+
+    /// Spawns the Lightwalletd process from the launch `command` and waits for the server to start.
+    fn spawn(
+        command: &mut std::process::Command,
+        logs_dir: &TempDir,
+    ) -> Result<Child, LaunchError> {
+        // lightwalletd appends to its log file so it is truncated to avoid matching the log of a previous launch
+        let lwd_log_file_path = logs_dir.path().join(logs::LIGHTWALLETD_LOG);
+        let _lwd_log_file = File::create(&lwd_log_file_path).unwrap();
+
+        let mut handle = command.spawn().map_err(|e| LaunchError::Spawn {
+            process_name: Process::Lightwalletd.to_string(),
+            source: Arc::new(e),
+        })?;
+
+        logs::write_logs(&mut handle, logs_dir);
+        launch::wait(
+            Process::Lightwalletd,
+            &mut handle,
+            logs_dir,
+            Some(lwd_log_file_path),
+            &["Starting insecure no-TLS (plaintext) server"],
+            &["error"],
+            &[],
+        )?;
+
+        Ok(handle)
+    }
+
+    /// Restarts the process after it has been stopped.
+    fn restart(&mut self) -> Result<(), LaunchError> {
+        self.handle = Self::spawn(&mut self.command, &self.logs_dir)?;
+
+        Ok(())
+    }
 }
 
 impl Indexer for Lightwalletd {
@@ -222,20 +672,24 @@ impl Indexer for Lightwalletd {
 
     type Config = LightwalletdConfig;
 
-    fn launch(config: Self::Config) -> Result<Self, LaunchError> {
+    async fn launch(config: Self::Config) -> Result<Self, LaunchError> {
         let logs_dir = tempfile::tempdir().unwrap();
         let lwd_log_file_path = logs_dir.path().join(logs::LIGHTWALLETD_LOG);
-        let _lwd_log_file = File::create(&lwd_log_file_path).unwrap();
-
         let data_dir = tempfile::tempdir().unwrap();
 
-        let port = network::pick_unused_port(config.listen_port);
+        let zcashd_conf = match (config.darkside, config.zcashd_conf) {
+            (true, _) => None,
+            (false, Some(zcashd_conf)) => Some(zcashd_conf),
+            (false, None) => return Err(LaunchError::MissingZcashdConf),
+        };
+
+        let port = network::pick_unused_port(config.listen_port)?;
         let config_dir = tempfile::tempdir().unwrap();
         let config_file_path = config::lightwalletd(
             config_dir.path(),
             port,
             lwd_log_file_path.clone(),
-            config.zcashd_conf.clone(),
+            zcashd_conf.clone(),
         )
         .unwrap();
 
@@ -250,26 +704,25 @@ impl Indexer for Lightwalletd {
                 data_dir.path().to_str().unwrap(),
                 "--log-file",
                 lwd_log_file_path.to_str().unwrap(),
-                "--zcash-conf-path",
-                config.zcashd_conf.to_str().unwrap(),
                 "--config",
                 config_file_path.to_str().unwrap(),
             ])
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
 
-        let mut handle = command.spawn().unwrap();
+        let network = match &zcashd_conf {
+            Some(zcashd_conf) => {
+                command.args(["--zcash-conf-path", zcashd_conf.to_str().unwrap()]);
+                config::zcashd_conf_network(zcashd_conf)
+                    .map_err(|e| LaunchError::ZcashdConf(Arc::new(e)))?
+            }
+            None => {
+                command.arg("--darkside-very-insecure");
+                Network::Regtest
+            }
+        };
 
-        logs::write_logs(&mut handle, &logs_dir);
-        launch::wait(
-            Process::Lightwalletd,
-            &mut handle,
-            &logs_dir,
-            Some(lwd_log_file_path),
-            &["Starting insecure no-TLS (plaintext) server"],
-            &["error"],
-            &[],
-        )?;
+        let handle = Self::spawn(&mut command, &logs_dir)?;
 
         Ok(Lightwalletd {
             handle,
@@ -277,6 +730,8 @@ impl Indexer for Lightwalletd {
             _data_dir: data_dir,
             logs_dir,
             config_dir,
+            network,
+            command,
         })
     }
 
@@ -284,6 +739,44 @@ impl Indexer for Lightwalletd {
         self.handle.kill().expect("lightwalletd couldn't be killed")
     }
 
+    fn checkpoint(&mut self) -> Result<Checkpoint, LaunchError> {
+        self.stop();
+        let _ = self.handle.wait();
+        let checkpoint = checkpoint::snapshot(self._data_dir.path());
+        self.restart()?;
+
+        checkpoint.map_err(|e| LaunchError::Checkpoint(Arc::new(e)))
+    }
+
+    fn rollback(&mut self, checkpoint: &Checkpoint) -> Result<(), LaunchError> {
+        self.stop();
+        let _ = self.handle.wait();
+        checkpoint::restore(checkpoint, self._data_dir.path())
+            .map_err(|e| LaunchError::Checkpoint(Arc::new(e)))?;
+        self.restart()
+    }
+
+    /// Lightwalletd does not support loading a state cache so caching is not supported.
+    async fn cache_state(&mut self, _state_cache: PathBuf) -> Result<(), StateCacheError> {
+        Err(StateCacheError::Unsupported("lightwalletd"))
+    }
+
+    fn wait_for_mempool_tx(
+        &self,
+        txid: TxId,
+        timeout: Duration,
+    ) -> impl std::future::Future<Output = Result<CompactTx, WaitError>> + Send {
+        wait_for_mempool_tx(self.port, txid, timeout)
+    }
+
+    fn wait_for_height(
+        &self,
+        height: BlockHeight,
+        timeout: Duration,
+    ) -> impl std::future::Future<Output = Result<(), WaitError>> + Send {
+        wait_for_height(self.port, height, timeout)
+    }
+
     fn config_dir(&self) -> &TempDir {
         &self.config_dir
     }
@@ -291,6 +784,18 @@ impl Indexer for Lightwalletd {
     fn logs_dir(&self) -> &TempDir {
         &self.logs_dir
     }
+
+    fn grpc_port(&self) -> Option<Port> {
+        Some(self.port)
+    }
+
+    fn network(&self) -> Option<Network> {
+        Some(self.network)
+    }
+
+    fn health_check(&self) -> impl std::future::Future<Output = Result<(), HealthError>> + Send {
+        health_check(self.port)
+    }
 }
 
 impl Drop for Lightwalletd {
@@ -299,6 +804,165 @@ impl Drop for Lightwalletd {
     }
 }
 
+/// Configuration for an indexer of any kind. See [`AnyIndexer`].
+///
+/// Defaults to Zainod.
+pub enum AnyIndexerConfig {
+    /// Zainod configuration
+    Zainod(ZainodConfig),
+    /// Lightwalletd configuration
+    Lightwalletd(LightwalletdConfig),
+}
+
+impl Default for AnyIndexerConfig {
+    fn default() -> Self {
+        AnyIndexerConfig::Zainod(ZainodConfig::default())
+    }
+}
+
+/// Holds an indexer process of any kind.
+///
+/// Used for running multiple heterogeneous indexers against one validator, see
+/// [`crate::LocalNet::attach_indexer`].
+pub enum AnyIndexer {
+    /// Zainod process
+    Zainod(Zainod),
+    /// Lightwalletd process
+    Lightwalletd(Lightwalletd),
+}
+
+impl AnyIndexer {
+    /// Returns the Zainod process, `None` if the indexer is not Zainod.
+    pub fn as_zainod(&self) -> Option<&Zainod> {
+        match self {
+            AnyIndexer::Zainod(zainod) => Some(zainod),
+            _ => None,
+        }
+    }
+
+    /// Returns the Lightwalletd process, `None` if the indexer is not Lightwalletd.
+    pub fn as_lightwalletd(&self) -> Option<&Lightwalletd> {
+        match self {
+            AnyIndexer::Lightwalletd(lightwalletd) => Some(lightwalletd),
+            _ => None,
+        }
+    }
+}
+
+impl Indexer for AnyIndexer {
+    /// Unused, the config filename depends on the kind of indexer. See [`Self::config_path`].
+    const CONFIG_FILENAME: &str = "";
+
+    type Config = AnyIndexerConfig;
+
+    async fn launch(config: Self::Config) -> Result<Self, LaunchError> {
+        match config {
+            AnyIndexerConfig::Zainod(config) => {
+                Ok(AnyIndexer::Zainod(Zainod::launch(config).await?))
+            }
+            AnyIndexerConfig::Lightwalletd(config) => Ok(AnyIndexer::Lightwalletd(
+                Lightwalletd::launch(config).await?,
+            )),
+        }
+    }
+
+    fn stop(&mut self) {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.stop(),
+            AnyIndexer::Lightwalletd(lightwalletd) => lightwalletd.stop(),
+        }
+    }
+
+    fn checkpoint(&mut self) -> Result<Checkpoint, LaunchError> {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.checkpoint(),
+            AnyIndexer::Lightwalletd(lightwalletd) => lightwalletd.checkpoint(),
+        }
+    }
+
+    fn rollback(&mut self, checkpoint: &Checkpoint) -> Result<(), LaunchError> {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.rollback(checkpoint),
+            AnyIndexer::Lightwalletd(lightwalletd) => lightwalletd.rollback(checkpoint),
+        }
+    }
+
+    async fn cache_state(&mut self, state_cache: PathBuf) -> Result<(), StateCacheError> {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.cache_state(state_cache).await,
+            AnyIndexer::Lightwalletd(lightwalletd) => lightwalletd.cache_state(state_cache).await,
+        }
+    }
+
+    async fn wait_for_mempool_tx(
+        &self,
+        txid: TxId,
+        timeout: Duration,
+    ) -> Result<CompactTx, WaitError> {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.wait_for_mempool_tx(txid, timeout).await,
+            AnyIndexer::Lightwalletd(lightwalletd) => {
+                lightwalletd.wait_for_mempool_tx(txid, timeout).await
+            }
+        }
+    }
+
+    async fn wait_for_height(
+        &self,
+        height: BlockHeight,
+        timeout: Duration,
+    ) -> Result<(), WaitError> {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.wait_for_height(height, timeout).await,
+            AnyIndexer::Lightwalletd(lightwalletd) => {
+                lightwalletd.wait_for_height(height, timeout).await
+            }
+        }
+    }
+
+    fn config_dir(&self) -> &TempDir {
+        match self {
+            AnyIndexer::Zainod(zainod) => Indexer::config_dir(zainod),
+            AnyIndexer::Lightwalletd(lightwalletd) => Indexer::config_dir(lightwalletd),
+        }
+    }
+
+    fn logs_dir(&self) -> &TempDir {
+        match self {
+            AnyIndexer::Zainod(zainod) => Indexer::logs_dir(zainod),
+            AnyIndexer::Lightwalletd(lightwalletd) => Indexer::logs_dir(lightwalletd),
+        }
+    }
+
+    fn config_path(&self) -> PathBuf {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.config_path(),
+            AnyIndexer::Lightwalletd(lightwalletd) => lightwalletd.config_path(),
+        }
+    }
+
+    fn grpc_port(&self) -> Option<Port> {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.grpc_port(),
+            AnyIndexer::Lightwalletd(lightwalletd) => lightwalletd.grpc_port(),
+        }
+    }
+
+    fn network(&self) -> Option<Network> {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.network(),
+            AnyIndexer::Lightwalletd(lightwalletd) => lightwalletd.network(),
+        }
+    }
+
+    async fn health_check(&self) -> Result<(), HealthError> {
+        match self {
+            AnyIndexer::Zainod(zainod) => zainod.health_check().await,
+            AnyIndexer::Lightwalletd(lightwalletd) => lightwalletd.health_check().await,
+        }
+    }
+}
+
 /// This struct is used to represent and manage an empty Indexer process.
 ///
 /// Dirs are created for integration.
@@ -316,7 +980,7 @@ impl Indexer for Empty {
 
     type Config = EmptyConfig;
 
-    fn launch(_config: Self::Config) -> Result<Self, LaunchError> {
+    async fn launch(_config: Self::Config) -> Result<Self, LaunchError> {
         let logs_dir = tempfile::tempdir().unwrap();
         let config_dir = tempfile::tempdir().unwrap();
 
@@ -328,6 +992,37 @@ impl Indexer for Empty {
 
     fn stop(&mut self) {}
 
+    /// Empty has no state so the returned checkpoint is empty.
+    fn checkpoint(&mut self) -> Result<Checkpoint, LaunchError> {
+        checkpoint::empty().map_err(|e| LaunchError::Checkpoint(Arc::new(e)))
+    }
+
+    fn rollback(&mut self, _checkpoint: &Checkpoint) -> Result<(), LaunchError> {
+        Ok(())
+    }
+
+    /// Empty has no state to cache.
+    async fn cache_state(&mut self, _state_cache: PathBuf) -> Result<(), StateCacheError> {
+        Err(StateCacheError::Unsupported("empty indexer"))
+    }
+
+    async fn wait_for_mempool_tx(
+        &self,
+        _txid: TxId,
+        _timeout: Duration,
+    ) -> Result<CompactTx, WaitError> {
+        Err(WaitError::NoIndexer)
+    }
+
+    /// Empty has no chain to sync so returns immediately.
+    async fn wait_for_height(
+        &self,
+        _height: BlockHeight,
+        _timeout: Duration,
+    ) -> Result<(), WaitError> {
+        Ok(())
+    }
+
     fn config_dir(&self) -> &TempDir {
         &self.config_dir
     }
@@ -335,6 +1030,19 @@ impl Indexer for Empty {
     fn logs_dir(&self) -> &TempDir {
         &self.logs_dir
     }
+
+    fn grpc_port(&self) -> Option<Port> {
+        None
+    }
+
+    fn network(&self) -> Option<Network> {
+        None
+    }
+
+    /// Empty has no process to fail so is always healthy.
+    async fn health_check(&self) -> Result<(), HealthError> {
+        Ok(())
+    }
 }
 
 impl Drop for Empty {
@@ -342,3 +1050,239 @@ impl Drop for Empty {
         self.stop();
     }
 }
+
+/// Writes the state cache metadata, recording the `network` and the chain tip `height` and `hash` of the cached state.
+fn write_state_cache_metadata(
+    state_cache: &Path,
+    network: Network,
+    height: u64,
+    hash: &str,
+) -> Result<(), StateCacheError> {
+    let metadata = serde_json::json!({
+        "network": network.to_string(),
+        "height": height,
+        "hash": hash,
+    });
+    std::fs::write(
+        state_cache.join(STATE_CACHE_METADATA_FILENAME),
+        metadata.to_string(),
+    )?;
+
+    Ok(())
+}
+
+/// Reads the state cache metadata and checks the cache was created on the same `network`.
+/// Returns the chain tip height and hash of the cached state.
+fn read_state_cache_metadata(
+    state_cache: &Path,
+    network: Network,
+) -> Result<(u32, String), StateCacheError> {
+    let metadata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        state_cache.join(STATE_CACHE_METADATA_FILENAME),
+    )?)
+    .map_err(|e| StateCacheError::InvalidMetadata(e.to_string()))?;
+
+    let cached_network = metadata["network"]
+        .as_str()
+        .ok_or_else(|| StateCacheError::InvalidMetadata(metadata.to_string()))?;
+    if cached_network != network.to_string() {
+        return Err(StateCacheError::NetworkMismatch {
+            cached: cached_network.to_string(),
+            expected: network.to_string(),
+        });
+    }
+    let height = metadata["height"]
+        .as_u64()
+        .and_then(|height| u32::try_from(height).ok())
+        .ok_or_else(|| StateCacheError::InvalidMetadata(metadata.to_string()))?;
+    let hash = metadata["hash"]
+        .as_str()
+        .ok_or_else(|| StateCacheError::InvalidMetadata(metadata.to_string()))?;
+
+    Ok((height, hash.to_string()))
+}
+
+/// Checks the chain tip of the `state_cache` is in the chain of the validator at `validator_rpc_client`.
+async fn validate_state_cache(
+    state_cache: &Path,
+    network: Network,
+    validator_rpc_client: &RpcClient,
+) -> Result<(), StateCacheError> {
+    let (height, hash) = read_state_cache_metadata(state_cache, network)?;
+
+    // the validator responds with an error if it has no block at the cached height
+    let validator_hash = match validator_rpc_client
+        .call("getblockhash", serde_json::json!([height]))
+        .await
+    {
+        Ok(validator_hash) => validator_hash,
+        Err(RpcError::Response { .. }) => serde_json::Value::Null,
+        Err(e) => return Err(e.into()),
+    };
+    if validator_hash.as_str() != Some(hash.as_str()) {
+        return Err(StateCacheError::ChainMismatch { height, hash });
+    }
+
+    Ok(())
+}
+
+/// Calls the `GetLightdInfo` gRPC of the indexer listening on `port`.
+async fn health_check(port: Port) -> Result<(), HealthError> {
+    let channel = tonic::transport::Channel::builder(network::localhost_uri(port))
+        .connect()
+        .await?;
+    CompactTxStreamerClient::new(channel)
+        .get_lightd_info(EmptyRequest {})
+        .await?;
+
+    Ok(())
+}
+
+/// Polls the `GetMempoolTx` gRPC of the indexer listening on `port` until the transaction with `txid` is served.
+async fn wait_for_mempool_tx(
+    port: Port,
+    txid: TxId,
+    timeout: Duration,
+) -> Result<CompactTx, WaitError> {
+    // the compact transaction hash is in reversed byte order, matching the RPC representation of the txid
+    let mut txid_bytes = txid.as_ref().to_vec();
+    txid_bytes.reverse();
+
+    let channel = tonic::transport::Channel::builder(network::localhost_uri(port))
+        .connect()
+        .await?;
+    let mut client = CompactTxStreamerClient::new(channel);
+
+    let start = std::time::Instant::now();
+    loop {
+        let mut response = client
+            .get_mempool_tx(Exclude { txid: Vec::new() })
+            .await?
+            .into_inner();
+        while let Some(compact_tx) = response.message().await? {
+            if compact_tx.hash == txid_bytes {
+                return Ok(compact_tx);
+            }
+        }
+        if start.elapsed() >= timeout {
+            return Err(WaitError::Timeout {
+                timeout,
+                waiting_for: format!("txid in indexer mempool: {txid}"),
+            });
+        }
+
+        tokio::time::sleep(mempool::POLL_INTERVAL).await;
+    }
+}
+
+/// Polls the `GetLatestBlock` gRPC of the indexer listening on `port` until the chain tip is at least `height`.
+async fn wait_for_height(
+    port: Port,
+    height: BlockHeight,
+    timeout: Duration,
+) -> Result<(), WaitError> {
+    let channel = tonic::transport::Channel::builder(network::localhost_uri(port))
+        .connect()
+        .await?;
+    let mut client = CompactTxStreamerClient::new(channel);
+
+    let start = std::time::Instant::now();
+    loop {
+        let latest_block = client.get_latest_block(ChainSpec {}).await?.into_inner();
+        if latest_block.height >= u64::from(height) {
+            return Ok(());
+        }
+        if start.elapsed() >= timeout {
+            return Err(WaitError::Timeout {
+                timeout,
+                waiting_for: format!("indexer chain height: {height}"),
+            });
+        }
+
+        tokio::time::sleep(mempool::POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{error::StateCacheError, network::Network};
+
+    use super::{
+        AnyIndexerConfig, ConnectValidator, LightwalletdConfig, ValidatorConnection, ZainodBackend,
+        ZainodConfig,
+    };
+
+    #[test]
+    fn state_cache_metadata() {
+        let state_cache = tempfile::tempdir().unwrap();
+
+        super::write_state_cache_metadata(state_cache.path(), Network::Regtest, 5, "0123abcd")
+            .unwrap();
+
+        assert_eq!(
+            super::read_state_cache_metadata(state_cache.path(), Network::Regtest).unwrap(),
+            (5, "0123abcd".to_string())
+        );
+        assert!(matches!(
+            super::read_state_cache_metadata(state_cache.path(), Network::Testnet),
+            Err(StateCacheError::NetworkMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn state_cache_metadata_missing() {
+        let state_cache = tempfile::tempdir().unwrap();
+
+        assert!(matches!(
+            super::read_state_cache_metadata(state_cache.path(), Network::Regtest),
+            Err(StateCacheError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn connect_validator() {
+        let connection = ValidatorConnection::new(
+            18232,
+            PathBuf::from("zcash.conf"),
+            PathBuf::from("chain_dir"),
+        );
+
+        let mut zainod_config = ZainodConfig {
+            zainod_bin: None,
+            listen_port: None,
+            enable_json_rpc: false,
+            json_rpc_listen_port: None,
+            validator_port: 0,
+            chain_cache: None,
+            network: Network::Regtest,
+            backend: ZainodBackend::State {
+                zebrad_cache_dir: PathBuf::new(),
+            },
+        };
+        zainod_config.connect(&connection);
+        assert_eq!(zainod_config.validator_port, 18232);
+        assert_eq!(
+            zainod_config.backend,
+            ZainodBackend::State {
+                zebrad_cache_dir: PathBuf::from("chain_dir")
+            }
+        );
+
+        let mut lightwalletd_config = AnyIndexerConfig::Lightwalletd(LightwalletdConfig {
+            lightwalletd_bin: None,
+            listen_port: None,
+            zcashd_conf: None,
+            darkside: false,
+        });
+        lightwalletd_config.connect(&connection);
+        let AnyIndexerConfig::Lightwalletd(lightwalletd_config) = lightwalletd_config else {
+            panic!("config should be lightwalletd");
+        };
+        assert_eq!(
+            lightwalletd_config.zcashd_conf,
+            Some(PathBuf::from("zcash.conf"))
+        );
+    }
+}
@@ -36,20 +36,41 @@
 //!
 
 /// The pub interface
+pub mod block;
+pub mod checkpoint;
 pub mod config;
 pub mod error;
+pub mod funding;
 pub mod indexer;
+pub mod mempool;
+pub mod metrics;
 pub mod network;
+pub mod producer;
+pub mod proxy;
+pub mod rpc;
 pub mod utils;
 pub mod validator;
 
 mod launch;
 mod logs;
 
+use std::{path::PathBuf, sync::Arc};
+
+use checkpoint::LocalNetCheckpoint;
+use error::{GenerateBlocksError, LaunchError};
 use indexer::{
-    Empty, EmptyConfig, Indexer, Lightwalletd, LightwalletdConfig, Zainod, ZainodConfig,
+    AnyIndexer, AnyIndexerConfig, ConnectValidator, Indexer, IndexerConfig, Lightwalletd,
+    ValidatorConnection, Zainod,
 };
-use validator::{Validator, Zcashd, ZcashdConfig, Zebrad, ZebradConfig};
+use proxy::FaultProxy;
+use validator::{Validator, ValidatorConfig};
+use zcash_protocol::consensus::BlockHeight;
+
+/// Time allowed for indexers to sync to newly generated blocks, see [`LocalNet::generate_blocks_and_sync`].
+pub const INDEXER_SYNC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Time allowed for transactions to arrive in the validator and indexer mempools.
+pub const MEMPOOL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 
 /// All processes currently supported
 #[derive(Clone, Copy)]
@@ -77,6 +98,11 @@ impl std::fmt::Display for Process {
 /// May be used to launch an indexer and validator together. This simplifies launching a Zcash test environment and
 /// managing multiple processes as well as allowing generic test framework of processes that implement the
 /// [`crate::validator::Validator`] or [`crate::indexer::Indexer`] trait.
+///
+/// Use `launch_with_proxy` to insert a [`crate::proxy::FaultProxy`] between the indexer and the validator's RPC port.
+///
+/// Additional indexers of any kind may be run against the same validator with `attach_indexer`, for example to
+/// test Zainod and Lightwalletd side by side.
 pub struct LocalNet<I, V>
 where
     I: Indexer,
@@ -84,6 +110,8 @@ where
 {
     indexer: I,
     validator: V,
+    proxy: Option<FaultProxy>,
+    additional_indexers: Vec<AnyIndexer>,
 }
 
 impl<I, V> LocalNet<I, V>
@@ -91,6 +119,63 @@ where
     I: Indexer,
     V: Validator,
 {
+    /// Returns a builder for launching LocalNet with default configs, see [`LocalNetBuilder`].
+    pub fn builder() -> LocalNetBuilder<I, V> {
+        LocalNetBuilder {
+            indexer_config: I::Config::default(),
+            validator_config: V::Config::default(),
+            proxy: false,
+        }
+    }
+
+    /// Launch LocalNet.
+    ///
+    /// The validator connection fields of the indexer config will be overwritten to match the validator, see
+    /// [`crate::indexer::ConnectValidator`].
+    ///
+    /// If the indexer fails to launch, the validator is stopped before the error is returned.
+    pub async fn launch(
+        mut indexer_config: I::Config,
+        validator_config: V::Config,
+    ) -> Result<Self, LaunchError> {
+        let validator = V::launch(validator_config).await?;
+        indexer_config.connect(&validator_connection(&validator, None)?);
+        // the validator is stopped on drop if the indexer fails to launch
+        let indexer = I::launch(indexer_config).await?;
+
+        Ok(LocalNet {
+            indexer,
+            validator,
+            proxy: None,
+            additional_indexers: Vec::new(),
+        })
+    }
+
+    /// Launch LocalNet with a [`crate::proxy::FaultProxy`] between the indexer and the validator.
+    ///
+    /// The validator connection fields of the indexer config will be overwritten to match the proxy, see
+    /// [`crate::indexer::ConnectValidator`].
+    ///
+    /// If the proxy or indexer fails to launch, the processes already started are stopped before the error is
+    /// returned.
+    pub async fn launch_with_proxy(
+        mut indexer_config: I::Config,
+        validator_config: V::Config,
+    ) -> Result<Self, LaunchError> {
+        let validator = V::launch(validator_config).await?;
+        let proxy = FaultProxy::launch(validator.rpc_port(), None)?;
+        indexer_config.connect(&validator_connection(&validator, Some(&proxy))?);
+        // the validator and proxy are stopped on drop if the indexer fails to launch
+        let indexer = I::launch(indexer_config).await?;
+
+        Ok(LocalNet {
+            indexer,
+            validator,
+            proxy: Some(proxy),
+            additional_indexers: Vec::new(),
+        })
+    }
+
     /// Gets indexer.
     pub fn indexer(&self) -> &I {
         &self.indexer
@@ -101,6 +186,27 @@ where
         &mut self.indexer
     }
 
+    /// Gets the additional indexers, in the order they were attached.
+    pub fn additional_indexers(&self) -> &[AnyIndexer] {
+        &self.additional_indexers
+    }
+
+    /// Gets the additional Zainod indexers, in the order they were attached. The primary indexer is not included, see
+    /// [`Self::indexer`].
+    pub fn additional_zainods(&self) -> impl Iterator<Item = &Zainod> {
+        self.additional_indexers
+            .iter()
+            .filter_map(AnyIndexer::as_zainod)
+    }
+
+    /// Gets the additional Lightwalletd indexers, in the order they were attached. The primary indexer is not
+    /// included, see [`Self::indexer`].
+    pub fn additional_lightwalletds(&self) -> impl Iterator<Item = &Lightwalletd> {
+        self.additional_indexers
+            .iter()
+            .filter_map(AnyIndexer::as_lightwalletd)
+    }
+
     /// Gets validator.
     pub fn validator(&self) -> &V {
         &self.validator
@@ -110,82 +216,226 @@ where
     pub fn validator_mut(&mut self) -> &mut V {
         &mut self.validator
     }
-}
 
-impl LocalNet<Zainod, Zcashd> {
-    /// Launch LocalNet.
+    /// Gets the fault-injecting proxy between the indexer and validator, if launched with a proxy.
+    pub fn proxy(&self) -> Option<&FaultProxy> {
+        self.proxy.as_ref()
+    }
+
+    /// Checkpoints the validator and indexer state, including any additional indexers.
+    /// See [`crate::validator::Validator::checkpoint`].
     ///
-    /// The `validator_port` field of [`crate::indexer::ZainodConfig`] will be overwritten to match the validator's RPC port.
-    pub async fn launch(mut indexer_config: ZainodConfig, validator_config: ZcashdConfig) -> Self {
-        let validator = Zcashd::launch(validator_config).await.unwrap();
-        indexer_config.validator_port = validator.port();
-        let indexer = Zainod::launch(indexer_config).unwrap();
+    /// Pass the returned checkpoint to [`Self::rollback`] to restore all processes to this exact state.
+    pub async fn checkpoint(&mut self) -> Result<LocalNetCheckpoint, LaunchError> {
+        let validator_checkpoint = self.validator.checkpoint().await?;
+        let indexer_checkpoint = self.indexer.checkpoint()?;
+        let additional_indexer_checkpoints = self
+            .additional_indexers
+            .iter_mut()
+            .map(|indexer| indexer.checkpoint())
+            .collect::<Result<Vec<_>, _>>()?;
 
-        LocalNet { indexer, validator }
+        Ok(LocalNetCheckpoint::new(
+            validator_checkpoint,
+            indexer_checkpoint,
+            additional_indexer_checkpoints,
+        ))
     }
-}
 
-impl LocalNet<Zainod, Zebrad> {
-    /// Launch LocalNet.
+    /// Generates `n` blocks and waits until the indexer and any additional indexers serve the new chain tip.
+    /// Returns the new chain height.
     ///
-    /// The `validator_port` field of [`crate::indexer::ZainodConfig`] will be overwritten to match the validator's RPC port.
-    pub async fn launch(mut indexer_config: ZainodConfig, validator_config: ZebradConfig) -> Self {
-        let validator = Zebrad::launch(validator_config).await.unwrap();
-        indexer_config.validator_port = validator.rpc_listen_port();
-        let indexer = Zainod::launch(indexer_config).unwrap();
+    /// Returns [`crate::error::GenerateBlocksError::IndexerSync`] if an indexer has not synced within
+    /// [`INDEXER_SYNC_TIMEOUT`].
+    pub async fn generate_blocks_and_sync(
+        &self,
+        n: u32,
+    ) -> Result<BlockHeight, GenerateBlocksError> {
+        self.validator.generate_blocks(n).await?;
+        let chain_height = self.validator.get_chain_height().await?;
+        self.indexer
+            .wait_for_height(chain_height, INDEXER_SYNC_TIMEOUT)
+            .await
+            .map_err(GenerateBlocksError::IndexerSync)?;
+        for indexer in &self.additional_indexers {
+            indexer
+                .wait_for_height(chain_height, INDEXER_SYNC_TIMEOUT)
+                .await
+                .map_err(GenerateBlocksError::IndexerSync)?;
+        }
 
-        LocalNet { indexer, validator }
+        Ok(chain_height)
     }
-}
 
-impl LocalNet<Lightwalletd, Zcashd> {
-    /// Launch LocalNet.
+    /// Restores the validator and indexer state, including any additional indexers, from the `checkpoint`.
     ///
-    /// The `validator_conf` field of [`crate::indexer::LightwalletdConfig`] will be overwritten to match the validator's config path.
-    pub async fn launch(
-        mut indexer_config: LightwalletdConfig,
-        validator_config: ZcashdConfig,
-    ) -> Self {
-        let validator = Zcashd::launch(validator_config).await.unwrap();
-        indexer_config.zcashd_conf = validator.config_path();
-        let indexer = Lightwalletd::launch(indexer_config).unwrap();
+    /// Additional indexers attached after the `checkpoint` was taken have no state to restore and would be left synced
+    /// to a chain that no longer exists, so they are stopped and removed.
+    pub async fn rollback(&mut self, checkpoint: &LocalNetCheckpoint) -> Result<(), LaunchError> {
+        self.additional_indexers
+            .truncate(checkpoint.additional_indexers().len());
+        self.validator.rollback(checkpoint.validator()).await?;
+        self.indexer.rollback(checkpoint.indexer())?;
+        for (indexer, indexer_checkpoint) in self
+            .additional_indexers
+            .iter_mut()
+            .zip(checkpoint.additional_indexers())
+        {
+            indexer.rollback(indexer_checkpoint)?;
+        }
 
-        LocalNet { indexer, validator }
+        Ok(())
     }
-}
 
-impl LocalNet<Lightwalletd, Zebrad> {
-    /// Launch LocalNet.
+    /// Launches an additional indexer of any kind against the validator and returns it.
     ///
-    /// The `validator_conf` field of [`crate::indexer::LightwalletdConfig`] will be overwritten to match the validator's config path.
-    pub async fn launch(
-        mut indexer_config: LightwalletdConfig,
-        validator_config: ZebradConfig,
-    ) -> Self {
-        let validator = Zebrad::launch(validator_config).await.unwrap();
-        indexer_config.zcashd_conf = validator.config_dir().path().join(config::ZCASHD_FILENAME);
-        let indexer = Lightwalletd::launch(indexer_config).unwrap();
+    /// The validator connection fields of the `config` are overwritten in the same way as [`Self::launch`], or
+    /// [`Self::launch_with_proxy`] if this LocalNet was launched with a proxy.
+    pub async fn attach_indexer(
+        &mut self,
+        mut config: AnyIndexerConfig,
+    ) -> Result<&AnyIndexer, LaunchError> {
+        config.connect(&validator_connection(&self.validator, self.proxy.as_ref())?);
+        let indexer = AnyIndexer::launch(config).await?;
+        self.additional_indexers.push(indexer);
 
-        LocalNet { indexer, validator }
+        Ok(self
+            .additional_indexers
+            .last()
+            .expect("indexer was just attached"))
     }
 }
 
-impl LocalNet<Empty, Zcashd> {
-    /// Launch LocalNet.
-    pub async fn launch(indexer_config: EmptyConfig, validator_config: ZcashdConfig) -> Self {
-        let validator = Zcashd::launch(validator_config).await.unwrap();
-        let indexer = Empty::launch(indexer_config).unwrap();
+/// Builder for launching a [`LocalNet`], returned by [`LocalNet::builder`].
+///
+/// The indexer and validator configs start from their defaults: a new regtest chain with default activation heights
+/// and binaries in $PATH. Use the chained methods to override the common config fields, or
+/// [`Self::indexer_config`] and [`Self::validator_config`] to modify any field.
+///
+/// # Example
+///
+/// ```ignore (incomplete)
+/// let local_net = LocalNet::<Zainod, Zebrad>::builder()
+///     .activation_heights(activation_heights)
+///     .with_proxy()
+///     .launch()
+///     .await?;
+/// ```
+pub struct LocalNetBuilder<I, V>
+where
+    I: Indexer,
+    V: Validator,
+{
+    indexer_config: I::Config,
+    validator_config: V::Config,
+    proxy: bool,
+}
 
-        LocalNet { indexer, validator }
+impl<I, V> LocalNetBuilder<I, V>
+where
+    I: Indexer,
+    V: Validator,
+{
+    /// Sets the indexer binary location.
+    pub fn indexer_bin(mut self, bin: PathBuf) -> Self {
+        self.indexer_config.set_bin(bin);
+        self
     }
-}
 
-impl LocalNet<Empty, Zebrad> {
-    /// Launch LocalNet.
-    pub async fn launch(indexer_config: EmptyConfig, validator_config: ZebradConfig) -> Self {
-        let validator = Zebrad::launch(validator_config).await.unwrap();
-        let indexer = Empty::launch(indexer_config).unwrap();
+    /// Sets the validator binary location.
+    pub fn validator_bin(mut self, bin: PathBuf) -> Self {
+        self.validator_config.set_bin(bin);
+        self
+    }
+
+    /// Sets the validator chain cache path.
+    pub fn chain_cache(mut self, chain_cache: PathBuf) -> Self {
+        self.validator_config.set_chain_cache(chain_cache);
+        self
+    }
+
+    /// Sets the local network upgrade activation heights.
+    pub fn activation_heights(mut self, activation_heights: network::ActivationHeights) -> Self {
+        self.validator_config
+            .set_activation_heights(activation_heights);
+        self
+    }
+
+    /// Sets the validator miner address.
+    pub fn miner_address(mut self, miner_address: &'static str) -> Self {
+        self.validator_config.set_miner_address(miner_address);
+        self
+    }
+
+    /// Inserts a [`crate::proxy::FaultProxy`] between the indexer and the validator, see
+    /// [`LocalNet::launch_with_proxy`].
+    pub fn with_proxy(mut self) -> Self {
+        self.proxy = true;
+        self
+    }
+
+    /// Modifies the indexer config.
+    pub fn indexer_config(mut self, modify: impl FnOnce(&mut I::Config)) -> Self {
+        modify(&mut self.indexer_config);
+        self
+    }
 
-        LocalNet { indexer, validator }
+    /// Modifies the validator config.
+    pub fn validator_config(mut self, modify: impl FnOnce(&mut V::Config)) -> Self {
+        modify(&mut self.validator_config);
+        self
     }
+
+    /// Launch LocalNet, see [`LocalNet::launch`].
+    pub async fn launch(self) -> Result<LocalNet<I, V>, LaunchError> {
+        if self.proxy {
+            LocalNet::launch_with_proxy(self.indexer_config, self.validator_config).await
+        } else {
+            LocalNet::launch(self.indexer_config, self.validator_config).await
+        }
+    }
+}
+
+/// Returns the connection info for indexers connecting to the `validator`, or the `proxy` if there is one.
+fn validator_connection<V: Validator>(
+    validator: &V,
+    proxy: Option<&FaultProxy>,
+) -> Result<ValidatorConnection, LaunchError> {
+    let chain_dir = validator.chain_dir().to_path_buf();
+    let connection = match proxy {
+        Some(proxy) => ValidatorConnection::new(
+            proxy.port(),
+            proxy_zcashd_conf(validator, proxy.port())?,
+            chain_dir,
+        ),
+        None => ValidatorConnection::new(
+            validator.rpc_port(),
+            validator.zcashd_conf_path(),
+            chain_dir,
+        ),
+    };
+
+    Ok(connection)
+}
+
+/// Writes a zcashd config file pointing to the `proxy_port` into a sub-directory of the validator's config directory.
+/// Returns the path to the config file.
+///
+/// Used for connecting Lightwalletd to the validator via a [`crate::proxy::FaultProxy`].
+fn proxy_zcashd_conf<V: Validator>(
+    validator: &V,
+    proxy_port: portpicker::Port,
+) -> Result<PathBuf, LaunchError> {
+    let proxy_config_dir = validator.config_dir().path().join("proxy");
+    std::fs::create_dir_all(&proxy_config_dir).map_err(|e| LaunchError::Config(Arc::new(e)))?;
+    let config_file_path = config::zcashd(
+        &proxy_config_dir,
+        proxy_port,
+        validator.activation_heights(),
+        None,
+        validator.network(),
+    )
+    .map_err(|e| LaunchError::Config(Arc::new(e)))?;
+
+    Ok(config_file_path)
 }
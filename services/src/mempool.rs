@@ -0,0 +1,127 @@
+//! Module for observing changes to a validator's mempool.
+
+use std::{collections::HashSet, time::Duration};
+
+use tokio_stream::wrappers::ReceiverStream;
+use zcash_primitives::transaction::TxId;
+
+use crate::{
+    error::RpcError,
+    rpc::{self, RpcClient},
+};
+
+/// Interval between mempool polls when waiting for transactions.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const CHANNEL_CAPACITY: usize = 100;
+
+/// A change to the set of transactions in the mempool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MempoolChange {
+    /// Transaction entered the mempool
+    Added(TxId),
+    /// Transaction left the mempool, either mined into a block or evicted
+    Removed(TxId),
+}
+
+/// Stream of mempool changes returned by [`crate::validator::Validator::mempool_changes`].
+pub type MempoolStream = ReceiverStream<Result<MempoolChange, RpcError>>;
+
+/// Gets the txids of all transactions in the mempool.
+pub(crate) async fn get_raw_mempool(rpc_client: &RpcClient) -> Result<Vec<TxId>, RpcError> {
+    let mempool = rpc_client
+        .call("getrawmempool", serde_json::json!([]))
+        .await?;
+
+    mempool
+        .as_array()
+        .ok_or_else(|| RpcError::InvalidResponse(mempool.to_string()))?
+        .iter()
+        .map(|txid| rpc::decode_hash(txid).map(TxId::from_bytes))
+        .collect()
+}
+
+/// Spawns a task polling the mempool every `poll_interval` and returns a stream of the changes.
+///
+/// Transactions already in the mempool on the first poll are yielded as [`MempoolChange::Added`].
+/// The task stops when the stream is dropped.
+pub(crate) fn watch(rpc_client: RpcClient, poll_interval: Duration) -> MempoolStream {
+    let (sender, receiver) = tokio::sync::mpsc::channel(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut previous = Vec::new();
+        loop {
+            match get_raw_mempool(&rpc_client).await {
+                Ok(current) => {
+                    for change in diff(&previous, &current) {
+                        if sender.send(Ok(change)).await.is_err() {
+                            return;
+                        }
+                    }
+                    previous = current;
+                }
+                Err(e) => {
+                    if sender.send(Err(e)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+
+            tokio::select! {
+                _ = sender.closed() => return,
+                _ = tokio::time::sleep(poll_interval) => {}
+            }
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
+
+/// Returns the changes from the `previous` mempool to the `current` mempool.
+/// Removed transactions are listed first, followed by added transactions in the order of `current`.
+fn diff(previous: &[TxId], current: &[TxId]) -> Vec<MempoolChange> {
+    let previous_set: HashSet<&TxId> = previous.iter().collect();
+    let current_set: HashSet<&TxId> = current.iter().collect();
+
+    previous
+        .iter()
+        .filter(|txid| !current_set.contains(txid))
+        .map(|txid| MempoolChange::Removed(*txid))
+        .chain(
+            current
+                .iter()
+                .filter(|txid| !previous_set.contains(txid))
+                .map(|txid| MempoolChange::Added(*txid)),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use zcash_primitives::transaction::TxId;
+
+    use super::MempoolChange;
+
+    #[test]
+    fn diff() {
+        let txids: Vec<TxId> = (0..4u8).map(|i| TxId::from_bytes([i; 32])).collect();
+
+        assert_eq!(super::diff(&[], &[]), Vec::new());
+        assert_eq!(
+            super::diff(&[], &txids[..2]),
+            vec![
+                MempoolChange::Added(txids[0]),
+                MempoolChange::Added(txids[1])
+            ]
+        );
+        assert_eq!(
+            super::diff(&txids[..3], &[txids[1], txids[3]]),
+            vec![
+                MempoolChange::Removed(txids[0]),
+                MempoolChange::Removed(txids[2]),
+                MempoolChange::Added(txids[3]),
+            ]
+        );
+        assert_eq!(super::diff(&txids, &txids), Vec::new());
+    }
+}
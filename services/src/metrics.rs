@@ -0,0 +1,210 @@
+//! Module for scraping Prometheus metrics from a process metrics endpoint.
+//!
+//! Example usage:
+//! ```ignore (incomplete)
+//! let metrics = zebrad.metrics().await?;
+//! assert_eq!(metrics.block_height(), Some(zebrad.get_chain_height().await?));
+//! ```
+
+use portpicker::Port;
+use zcash_protocol::consensus::BlockHeight;
+
+use crate::{error::EndpointError, network};
+
+/// Zebrad metric for the height of the best verified block.
+pub const BLOCK_HEIGHT: &str = "zcash_chain_verified_block_height";
+/// Zebrad metric for the number of transactions in the mempool.
+pub const MEMPOOL_SIZE: &str = "zcash_mempool_size_transactions";
+/// Zebrad metric for the number of connected peers.
+pub const PEER_COUNT: &str = "zcash_net_peers";
+
+/// A single metric sample.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    /// Metric name
+    pub name: String,
+    /// Label names and values
+    pub labels: Vec<(String, String)>,
+    /// Sample value
+    pub value: f64,
+}
+
+/// Metrics parsed from the Prometheus text exposition format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metrics {
+    /// All samples, in the order they were exposed
+    samples: Vec<Sample>,
+}
+
+impl Metrics {
+    /// Returns all samples.
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    /// Returns the value of the metric `name`, summed over all label sets.
+    /// Returns `None` if the metric was not exposed.
+    pub fn value(&self, name: &str) -> Option<f64> {
+        self.samples
+            .iter()
+            .filter(|sample| sample.name == name)
+            .map(|sample| sample.value)
+            .reduce(|total, value| total + value)
+    }
+
+    /// Returns the height of the best verified block.
+    pub fn block_height(&self) -> Option<BlockHeight> {
+        self.value(BLOCK_HEIGHT)
+            .map(|height| BlockHeight::from_u32(height as u32))
+    }
+
+    /// Returns the number of transactions in the mempool.
+    pub fn mempool_size(&self) -> Option<usize> {
+        self.value(MEMPOOL_SIZE).map(|size| size as usize)
+    }
+
+    /// Returns the number of connected peers.
+    pub fn peer_count(&self) -> Option<usize> {
+        self.value(PEER_COUNT).map(|count| count as usize)
+    }
+}
+
+impl std::str::FromStr for Metrics {
+    type Err = EndpointError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let samples = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                parse_sample(line).ok_or_else(|| EndpointError::InvalidResponse(line.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Metrics { samples })
+    }
+}
+
+/// Scrapes the metrics endpoint listening on `port` on localhost.
+pub async fn scrape(port: Port) -> Result<Metrics, EndpointError> {
+    let response = reqwest::get(format!("{}:{}/metrics", network::LOCALHOST_IPV4, port)).await?;
+
+    response_text(response).await?.parse()
+}
+
+/// Returns the body of a successful endpoint `response`.
+pub(crate) async fn response_text(response: reqwest::Response) -> Result<String, EndpointError> {
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(EndpointError::HttpStatus {
+            status: status.as_u16(),
+            body,
+        });
+    }
+
+    Ok(body)
+}
+
+/// Parses a sample line of the form `name{label="value",...} value [timestamp]`.
+fn parse_sample(line: &str) -> Option<Sample> {
+    let name_end = line
+        .find(|c: char| c == '{' || c.is_whitespace())
+        .unwrap_or(line.len());
+    let name = &line[..name_end];
+    let mut rest = &line[name_end..];
+
+    let mut labels = Vec::new();
+    if let Some(label_set) = rest.strip_prefix('{') {
+        rest = label_set;
+        loop {
+            rest = rest.trim_start_matches([',', ' ']);
+            if let Some(after_label_set) = rest.strip_prefix('}') {
+                rest = after_label_set;
+                break;
+            }
+
+            let (label_name, after_name) = rest.split_once('=')?;
+            let (label_value, after_value) = parse_label_value(after_name.strip_prefix('"')?)?;
+            labels.push((label_name.trim().to_string(), label_value));
+            rest = after_value;
+        }
+    }
+
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(Sample {
+        name: name.to_string(),
+        labels,
+        value,
+    })
+}
+
+/// Parses an escaped label value up to its closing quote. Returns the value and the remaining input.
+fn parse_label_value(input: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &input[i + 1..])),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                escaped => value.push(escaped),
+            },
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use zcash_protocol::consensus::BlockHeight;
+
+    use super::{Metrics, Sample};
+
+    const METRICS: &str = r#"# TYPE zcash_chain_verified_block_height gauge
+zcash_chain_verified_block_height 52
+
+# TYPE zcash_mempool_size_transactions gauge
+zcash_mempool_size_transactions 3
+# TYPE zcash_net_peers gauge
+zcash_net_peers{state="ready"} 2
+zcash_net_peers{state="responded", note="a \"quoted\" \\ value"} 1 1700000000000
+"#;
+
+    #[test]
+    fn parse_metrics() {
+        let metrics: Metrics = METRICS.parse().unwrap();
+
+        assert_eq!(metrics.samples().len(), 4);
+        assert_eq!(
+            metrics.samples()[3],
+            Sample {
+                name: "zcash_net_peers".to_string(),
+                labels: vec![
+                    ("state".to_string(), "responded".to_string()),
+                    ("note".to_string(), r#"a "quoted" \ value"#.to_string()),
+                ],
+                value: 1.0,
+            }
+        );
+        assert_eq!(metrics.block_height(), Some(BlockHeight::from_u32(52)));
+        assert_eq!(metrics.mempool_size(), Some(3));
+        assert_eq!(metrics.peer_count(), Some(3));
+        assert_eq!(metrics.value("zebra_unknown_metric"), None);
+    }
+
+    #[test]
+    fn parse_invalid_metrics() {
+        assert!("zcash_net_peers{state=\"ready} 2"
+            .parse::<Metrics>()
+            .is_err());
+        assert!("zcash_net_peers".parse::<Metrics>().is_err());
+    }
+}
@@ -2,11 +2,16 @@
 
 use portpicker::Port;
 use zcash_primitives::consensus::BlockHeight;
+use zcash_protocol::consensus::{
+    BranchId, NetworkType, NetworkUpgrade, Parameters, MAIN_NETWORK, TEST_NETWORK,
+};
+
+use crate::error::LaunchError;
 
 pub(crate) const LOCALHOST_IPV4: &str = "http://127.0.0.1";
 
 /// Network types
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Network {
     /// Regtest
     Regtest,
@@ -16,6 +21,49 @@ pub enum Network {
     Mainnet,
 }
 
+impl Network {
+    /// Returns the consensus branch ID active at `height`.
+    ///
+    /// `activation_heights` are only used for the `Regtest` variant.
+    pub fn branch_id(
+        &self,
+        activation_heights: &ActivationHeights,
+        height: BlockHeight,
+    ) -> BranchId {
+        match self {
+            Self::Regtest => activation_heights.branch_id(height),
+            Self::Testnet => BranchId::for_height(&TEST_NETWORK, height),
+            Self::Mainnet => BranchId::for_height(&MAIN_NETWORK, height),
+        }
+    }
+
+    /// Returns the zebra-chain network parameters, used for selecting the network upgrade of Zebrad block proposals.
+    ///
+    /// `activation_heights` are only used for the `Regtest` variant.
+    pub(crate) fn zebra_network(
+        &self,
+        activation_heights: &ActivationHeights,
+    ) -> zebra_chain::parameters::Network {
+        match self {
+            Self::Regtest => zebra_chain::parameters::Network::new_regtest(
+                zebra_chain::parameters::testnet::ConfiguredActivationHeights {
+                    overwinter: Some(activation_heights.overwinter.into()),
+                    sapling: Some(activation_heights.sapling.into()),
+                    blossom: Some(activation_heights.blossom.into()),
+                    heartwood: Some(activation_heights.heartwood.into()),
+                    canopy: Some(activation_heights.canopy.into()),
+                    nu5: Some(activation_heights.nu5.into()),
+                    nu6: Some(activation_heights.nu6.into()),
+                    // later network upgrades are not supported and are never activated
+                    ..Default::default()
+                },
+            ),
+            Self::Testnet => zebra_chain::parameters::Network::new_default_testnet(),
+            Self::Mainnet => zebra_chain::parameters::Network::Mainnet,
+        }
+    }
+}
+
 impl std::fmt::Display for Network {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -27,6 +75,8 @@ impl std::fmt::Display for Network {
 }
 
 /// Activation heights for local network upgrades
+///
+/// Network upgrades after Nu6 are not supported and are never activated on the local network.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ActivationHeights {
     /// Overwinter network upgrade activation height
@@ -45,6 +95,13 @@ pub struct ActivationHeights {
     pub nu6: BlockHeight,
 }
 
+impl ActivationHeights {
+    /// Returns the consensus branch ID active at `height`.
+    pub fn branch_id(&self, height: BlockHeight) -> BranchId {
+        BranchId::for_height(&RegtestParameters(*self), height)
+    }
+}
+
 impl Default for ActivationHeights {
     fn default() -> Self {
         Self {
@@ -59,16 +116,39 @@ impl Default for ActivationHeights {
     }
 }
 
+/// Consensus parameters of a regtest network with the given activation heights, used for building transactions.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RegtestParameters(pub(crate) ActivationHeights);
+
+impl Parameters for RegtestParameters {
+    fn network_type(&self) -> NetworkType {
+        NetworkType::Regtest
+    }
+
+    fn activation_height(&self, nu: NetworkUpgrade) -> Option<BlockHeight> {
+        let activation_heights = &self.0;
+        match nu {
+            NetworkUpgrade::Overwinter => Some(activation_heights.overwinter),
+            NetworkUpgrade::Sapling => Some(activation_heights.sapling),
+            NetworkUpgrade::Blossom => Some(activation_heights.blossom),
+            NetworkUpgrade::Heartwood => Some(activation_heights.heartwood),
+            NetworkUpgrade::Canopy => Some(activation_heights.canopy),
+            NetworkUpgrade::Nu5 => Some(activation_heights.nu5),
+            NetworkUpgrade::Nu6 => Some(activation_heights.nu6),
+        }
+    }
+}
+
 /// Checks `fixed_port` is not in use.
 /// If `fixed_port` is `None`, returns a random free port between 15_000 and 25_000.
-pub(crate) fn pick_unused_port(fixed_port: Option<Port>) -> Port {
+pub(crate) fn pick_unused_port(fixed_port: Option<Port>) -> Result<Port, LaunchError> {
     if let Some(port) = fixed_port {
         if !portpicker::is_free(port) {
-            panic!("Fixed port is not free!");
+            return Err(LaunchError::PortUnavailable(port));
         };
-        port
+        Ok(port)
     } else {
-        portpicker::pick_unused_port().expect("No ports free!")
+        portpicker::pick_unused_port().ok_or(LaunchError::NoFreePort)
     }
 }
 
@@ -76,3 +156,89 @@ pub(crate) fn pick_unused_port(fixed_port: Option<Port>) -> Port {
 pub fn localhost_uri(port: Port) -> http::Uri {
     format!("{}:{}", LOCALHOST_IPV4, port).try_into().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use zcash_protocol::consensus::BranchId;
+    use zebra_chain::{block::Height, parameters::NetworkUpgrade};
+
+    use crate::error::LaunchError;
+
+    use super::{pick_unused_port, ActivationHeights, Network};
+
+    #[test]
+    fn branch_id() {
+        let activation_heights = ActivationHeights {
+            overwinter: 1.into(),
+            sapling: 2.into(),
+            blossom: 3.into(),
+            heartwood: 4.into(),
+            canopy: 5.into(),
+            nu5: 6.into(),
+            nu6: 7.into(),
+        };
+
+        let branch_ids: Vec<BranchId> = (0..=8)
+            .map(|height| activation_heights.branch_id(height.into()))
+            .collect();
+
+        assert_eq!(
+            branch_ids,
+            vec![
+                BranchId::Sprout,
+                BranchId::Overwinter,
+                BranchId::Sapling,
+                BranchId::Blossom,
+                BranchId::Heartwood,
+                BranchId::Canopy,
+                BranchId::Nu5,
+                BranchId::Nu6,
+                BranchId::Nu6,
+            ]
+        );
+    }
+
+    #[test]
+    fn zebra_network_upgrade() {
+        let activation_heights = ActivationHeights {
+            overwinter: 1.into(),
+            sapling: 2.into(),
+            blossom: 3.into(),
+            heartwood: 4.into(),
+            canopy: 5.into(),
+            nu5: 6.into(),
+            nu6: 7.into(),
+        };
+        let zebra_network = Network::Regtest.zebra_network(&activation_heights);
+
+        let network_upgrades: Vec<NetworkUpgrade> = (0..=8)
+            .map(|height| NetworkUpgrade::current(&zebra_network, Height(height)))
+            .collect();
+
+        assert_eq!(
+            network_upgrades,
+            vec![
+                NetworkUpgrade::Genesis,
+                NetworkUpgrade::Overwinter,
+                NetworkUpgrade::Sapling,
+                NetworkUpgrade::Blossom,
+                NetworkUpgrade::Heartwood,
+                NetworkUpgrade::Canopy,
+                NetworkUpgrade::Nu5,
+                NetworkUpgrade::Nu6,
+                NetworkUpgrade::Nu6,
+            ]
+        );
+    }
+
+    #[test]
+    fn pick_unused_port_busy() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(matches!(
+            pick_unused_port(Some(port)),
+            Err(LaunchError::PortUnavailable(busy_port)) if busy_port == port
+        ));
+    }
+}
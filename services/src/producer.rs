@@ -0,0 +1,110 @@
+//! Module for producing blocks in the background, simulating a live chain.
+//!
+//! Example usage:
+//! ```ignore (incomplete)
+//! let producer = validator.start_block_production(BlockProduction::Interval(Duration::from_millis(500)));
+//! // run the client under test against a chain that advances on its own...
+//! let blocks_produced = producer.stop().await?;
+//! ```
+
+use std::{future::Future, time::Duration};
+
+use tokio::{sync::oneshot, task::JoinHandle};
+
+use crate::{
+    error::GenerateBlocksError,
+    mempool::{self, POLL_INTERVAL},
+    rpc::RpcClient,
+};
+
+/// Selects when blocks are produced by [`crate::validator::Validator::start_block_production`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockProduction {
+    /// Produce a block every interval
+    Interval(Duration),
+    /// Produce a block whenever transactions arrive in the mempool
+    MempoolArrival,
+}
+
+/// Handle to a background block production task, see [`crate::validator::Validator::start_block_production`].
+///
+/// Block production stops when the handle is dropped.
+pub struct BlockProducer {
+    /// Sender for signalling the task to stop
+    stop: Option<oneshot::Sender<()>>,
+    /// Block production task, resolving to the number of blocks produced
+    task: JoinHandle<Result<u32, GenerateBlocksError>>,
+}
+
+impl BlockProducer {
+    /// Spawns a task calling `generate_block` according to the `block_production` mode.
+    ///
+    /// The mempool of the validator at `rpc_client` is polled for [`BlockProduction::MempoolArrival`].
+    pub(crate) fn spawn<F, Fut>(
+        rpc_client: RpcClient,
+        block_production: BlockProduction,
+        mut generate_block: F,
+    ) -> Self
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), GenerateBlocksError>> + Send,
+    {
+        let (stop, mut stopped) = oneshot::channel();
+
+        let task = tokio::spawn(async move {
+            let period = match block_production {
+                BlockProduction::Interval(interval) => interval,
+                BlockProduction::MempoolArrival => POLL_INTERVAL,
+            };
+            let mut ticks = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            let mut blocks_produced = 0;
+            loop {
+                tokio::select! {
+                    _ = &mut stopped => return Ok(blocks_produced),
+                    _ = ticks.tick() => {}
+                }
+
+                if block_production == BlockProduction::MempoolArrival
+                    && mempool::get_raw_mempool(&rpc_client).await?.is_empty()
+                {
+                    continue;
+                }
+                generate_block().await?;
+                blocks_produced += 1;
+            }
+        });
+
+        BlockProducer {
+            stop: Some(stop),
+            task,
+        }
+    }
+
+    /// Returns `true` if block production is running. Block production stops early if a block cannot be generated.
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Stops block production, waiting for any block being generated. Returns the number of blocks produced.
+    ///
+    /// Returns the error that stopped block production early, if any.
+    pub async fn stop(mut self) -> Result<u32, GenerateBlocksError> {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+
+        (&mut self.task)
+            .await
+            .expect("block production task should not panic")
+    }
+}
+
+impl Drop for BlockProducer {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+    }
+}
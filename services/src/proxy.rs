@@ -0,0 +1,411 @@
+//! Module for the fault-injecting TCP proxy that can be inserted between an indexer and its validator.
+//!
+//! The proxy forwards all traffic between a listen port and a target port and allows faults to be injected at
+//! runtime, for testing how indexers behave when the validator RPC is slow, unreachable or misbehaving.
+//!
+//! Example usage:
+//! ```ignore (incomplete)
+//! let proxy = FaultProxy::launch(validator.port(), None).unwrap();
+//! // point the indexer at `proxy.port()` instead of the validator port...
+//! proxy.set_latency(std::time::Duration::from_millis(500));
+//! proxy.reset_connections();
+//! proxy.set_blackhole(true);
+//! proxy.clear_faults();
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{Read as _, Write as _},
+    net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use portpicker::Port;
+
+use crate::{error::LaunchError, network};
+
+const BUFFER_SIZE: usize = 8192;
+const BANDWIDTH_SLICES_PER_SECOND: u64 = 10;
+
+/// Faults applied to traffic passing through a [`FaultProxy`].
+///
+/// The default value forwards all traffic unaltered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Faults {
+    /// Delay added before forwarding each chunk of data, in both directions.
+    pub latency: Duration,
+    /// Maximum throughput in bytes per second, in each direction. `None` is unlimited.
+    pub bandwidth_limit: Option<u64>,
+    /// Accept connections and read all data but never forward anything, in either direction.
+    pub blackhole: bool,
+    /// Close each connection once this many bytes have been forwarded from the target (validator) back to the
+    /// client (indexer), producing partial responses. `None` forwards complete responses.
+    pub truncate_responses: Option<usize>,
+    /// Close new connections immediately after they are accepted.
+    pub refuse_connections: bool,
+}
+
+/// State shared between the proxy handle and its worker threads.
+#[derive(Default)]
+struct Shared {
+    faults: Mutex<Faults>,
+    connections: Mutex<HashMap<u64, [TcpStream; 2]>>,
+    next_connection_id: AtomicU64,
+    shutdown: AtomicBool,
+}
+
+impl Shared {
+    fn faults(&self) -> Faults {
+        *self.faults.lock().unwrap()
+    }
+
+    fn close_connection(&self, id: u64) {
+        if let Some(streams) = self.connections.lock().unwrap().remove(&id) {
+            for stream in streams {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+    }
+}
+
+/// Direction that data is being forwarded through the proxy.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Client (indexer) to target (validator).
+    Upstream,
+    /// Target (validator) to client (indexer).
+    Downstream,
+}
+
+/// This struct is used to represent and manage an in-process fault-injecting TCP proxy.
+///
+/// All connections accepted on [`Self::port`] are forwarded to the target port on localhost. Faults are controlled
+/// at runtime with the setter methods and take effect on the next chunk of forwarded data.
+///
+/// The proxy is shut down and all connections are closed when dropped.
+pub struct FaultProxy {
+    /// Listen port
+    port: Port,
+    /// Target port
+    target_port: Port,
+    /// State shared with worker threads
+    shared: Arc<Shared>,
+    /// Listener thread handle
+    listener_handle: Option<JoinHandle<()>>,
+}
+
+impl FaultProxy {
+    /// Launch the proxy, forwarding connections on `listen_port` to `target_port` on localhost.
+    ///
+    /// If `listen_port` is `None`, a port is picked at random between 15000-25000.
+    pub fn launch(target_port: Port, listen_port: Option<Port>) -> Result<Self, LaunchError> {
+        let port = network::pick_unused_port(listen_port)?;
+        let listener = TcpListener::bind(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+            .map_err(|e| LaunchError::Proxy(Arc::new(e)))?;
+        let shared = Arc::new(Shared::default());
+
+        let listener_shared = shared.clone();
+        let listener_handle =
+            std::thread::spawn(move || accept_connections(listener, target_port, listener_shared));
+
+        Ok(FaultProxy {
+            port,
+            target_port,
+            shared,
+            listener_handle: Some(listener_handle),
+        })
+    }
+
+    /// Returns the port the proxy is listening on.
+    pub fn port(&self) -> Port {
+        self.port
+    }
+
+    /// Returns the port the proxy is forwarding to.
+    pub fn target_port(&self) -> Port {
+        self.target_port
+    }
+
+    /// Returns the faults currently applied.
+    pub fn faults(&self) -> Faults {
+        self.shared.faults()
+    }
+
+    /// Replaces all faults currently applied.
+    pub fn set_faults(&self, faults: Faults) {
+        *self.shared.faults.lock().unwrap() = faults;
+    }
+
+    /// Removes all faults so traffic is forwarded unaltered.
+    pub fn clear_faults(&self) {
+        self.set_faults(Faults::default());
+    }
+
+    /// Sets the delay added before forwarding each chunk of data.
+    pub fn set_latency(&self, latency: Duration) {
+        self.shared.faults.lock().unwrap().latency = latency;
+    }
+
+    /// Sets the maximum throughput in bytes per second. `None` is unlimited.
+    pub fn set_bandwidth_limit(&self, bytes_per_second: Option<u64>) {
+        self.shared.faults.lock().unwrap().bandwidth_limit = bytes_per_second;
+    }
+
+    /// Enables or disables blackholing of all traffic.
+    pub fn set_blackhole(&self, blackhole: bool) {
+        self.shared.faults.lock().unwrap().blackhole = blackhole;
+    }
+
+    /// Sets the number of response bytes forwarded before each connection is closed. `None` is unlimited.
+    pub fn set_truncate_responses(&self, bytes: Option<usize>) {
+        self.shared.faults.lock().unwrap().truncate_responses = bytes;
+    }
+
+    /// Enables or disables closing new connections immediately after they are accepted.
+    pub fn set_refuse_connections(&self, refuse: bool) {
+        self.shared.faults.lock().unwrap().refuse_connections = refuse;
+    }
+
+    /// Closes all currently open connections. New connections are still accepted unless
+    /// [`Self::set_refuse_connections`] is enabled.
+    pub fn reset_connections(&self) {
+        let connections = std::mem::take(&mut *self.shared.connections.lock().unwrap());
+        for stream in connections.into_values().flatten() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Returns the number of currently open connections.
+    pub fn connection_count(&self) -> usize {
+        self.shared.connections.lock().unwrap().len()
+    }
+
+    /// Stop the proxy and close all connections.
+    pub fn stop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        // wake the listener so it observes the shutdown flag
+        let _ = TcpStream::connect(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), self.port));
+        if let Some(handle) = self.listener_handle.take() {
+            if handle.join().is_err() {
+                tracing::error!("proxy listener thread panicked");
+            }
+        }
+        self.reset_connections();
+    }
+}
+
+impl Drop for FaultProxy {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn accept_connections(listener: TcpListener, target_port: Port, shared: Arc<Shared>) {
+    for client in listener.incoming() {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let client = match client {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!("proxy failed to accept connection: {e}");
+                continue;
+            }
+        };
+        if shared.faults().refuse_connections {
+            let _ = client.shutdown(Shutdown::Both);
+            continue;
+        }
+        let target =
+            match TcpStream::connect(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), target_port)) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::warn!("proxy failed to connect to target port {target_port}: {e}");
+                    let _ = client.shutdown(Shutdown::Both);
+                    continue;
+                }
+            };
+
+        if let Err(e) = spawn_connection(client, target, &shared) {
+            tracing::warn!("proxy failed to set up connection: {e}");
+        }
+    }
+}
+
+fn spawn_connection(
+    client: TcpStream,
+    target: TcpStream,
+    shared: &Arc<Shared>,
+) -> std::io::Result<()> {
+    let id = shared.next_connection_id.fetch_add(1, Ordering::SeqCst);
+    shared
+        .connections
+        .lock()
+        .unwrap()
+        .insert(id, [client.try_clone()?, target.try_clone()?]);
+
+    let upstream = (client.try_clone()?, target.try_clone()?);
+    let upstream_shared = shared.clone();
+    std::thread::spawn(move || {
+        pump(
+            upstream.0,
+            upstream.1,
+            Direction::Upstream,
+            &upstream_shared,
+        );
+        upstream_shared.close_connection(id);
+    });
+
+    let downstream_shared = shared.clone();
+    std::thread::spawn(move || {
+        pump(target, client, Direction::Downstream, &downstream_shared);
+        downstream_shared.close_connection(id);
+    });
+
+    Ok(())
+}
+
+/// Forwards data from `from` to `to`, applying the current faults to each chunk, until either side closes.
+fn pump(mut from: TcpStream, mut to: TcpStream, direction: Direction, shared: &Shared) {
+    let mut buffer = [0u8; BUFFER_SIZE];
+    let mut forwarded: usize = 0;
+
+    loop {
+        let faults = shared.faults();
+        let read_size = match faults.bandwidth_limit {
+            Some(limit) => ((limit / BANDWIDTH_SLICES_PER_SECOND).max(1) as usize).min(BUFFER_SIZE),
+            None => BUFFER_SIZE,
+        };
+        let n = match from.read(&mut buffer[..read_size]) {
+            Ok(0) | Err(_) => return,
+            Ok(n) => n,
+        };
+
+        // faults may have changed while blocked on the read
+        let faults = shared.faults();
+        if faults.blackhole {
+            continue;
+        }
+        if !faults.latency.is_zero() {
+            std::thread::sleep(faults.latency);
+        }
+        if let Some(limit) = faults.bandwidth_limit {
+            std::thread::sleep(Duration::from_secs_f64(n as f64 / limit.max(1) as f64));
+        }
+
+        let mut chunk = &buffer[..n];
+        let mut truncated = false;
+        if direction == Direction::Downstream {
+            if let Some(limit) = faults.truncate_responses {
+                let remaining = limit.saturating_sub(forwarded);
+                if chunk.len() >= remaining {
+                    chunk = &chunk[..remaining];
+                    truncated = true;
+                }
+            }
+        }
+
+        if to.write_all(chunk).is_err() {
+            return;
+        }
+        forwarded += chunk.len();
+        if truncated {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read as _, Write as _},
+        net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+        time::Duration,
+    };
+
+    use super::FaultProxy;
+
+    /// Launches a TCP server on a random port that echoes all received data back to the sender.
+    fn echo_server() -> u16 {
+        let listener = TcpListener::bind(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                std::thread::spawn(move || {
+                    let mut reader = stream.try_clone().unwrap();
+                    let _ = std::io::copy(&mut reader, &mut stream);
+                });
+            }
+        });
+        port
+    }
+
+    fn connect(proxy: &FaultProxy) -> TcpStream {
+        let stream =
+            TcpStream::connect(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), proxy.port())).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        stream
+    }
+
+    #[test]
+    fn forwards_traffic() {
+        let proxy = FaultProxy::launch(echo_server(), None).unwrap();
+        let mut stream = connect(&proxy);
+
+        stream.write_all(b"hello validator").unwrap();
+        let mut response = [0u8; 15];
+        stream.read_exact(&mut response).unwrap();
+
+        assert_eq!(&response, b"hello validator");
+    }
+
+    #[test]
+    fn truncates_responses() {
+        let proxy = FaultProxy::launch(echo_server(), None).unwrap();
+        proxy.set_truncate_responses(Some(5));
+        let mut stream = connect(&proxy);
+
+        stream.write_all(b"hello validator").unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+
+        assert_eq!(response, b"hello");
+    }
+
+    #[test]
+    fn blackholes_traffic() {
+        let proxy = FaultProxy::launch(echo_server(), None).unwrap();
+        proxy.set_blackhole(true);
+        let mut stream = connect(&proxy);
+        stream
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .unwrap();
+
+        stream.write_all(b"hello validator").unwrap();
+        let mut response = [0u8; 15];
+
+        assert!(stream.read(&mut response).is_err());
+    }
+
+    #[test]
+    fn resets_connections() {
+        let proxy = FaultProxy::launch(echo_server(), None).unwrap();
+        let mut stream = connect(&proxy);
+        stream.write_all(b"hello").unwrap();
+        let mut response = [0u8; 5];
+        stream.read_exact(&mut response).unwrap();
+
+        proxy.reset_connections();
+
+        let mut response = Vec::new();
+        assert!(matches!(stream.read_to_end(&mut response), Ok(0) | Err(_)));
+    }
+}
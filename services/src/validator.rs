@@ -1,29 +1,56 @@
 //! Module for the structs that represent and manage the validator/full-node processes i.e. Zebrad.
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::Child,
+    sync::{Arc, Mutex},
 };
 
-use zcash_protocol::consensus::BlockHeight;
+#[cfg(feature = "zebrad-funding")]
+use zcash_primitives::transaction::components::{OutPoint, TxOut};
+use zcash_primitives::{
+    block::BlockHash,
+    transaction::{Transaction, TxId},
+};
+use zcash_protocol::{consensus::BlockHeight, value::Zatoshis, PoolType, ShieldedProtocol};
 
 use getset::{CopyGetters, Getters};
 use portpicker::Port;
 use tempfile::TempDir;
-use zebra_chain::{parameters::NetworkUpgrade, serialization::ZcashSerialize as _};
-use zebra_node_services::rpc_client::RpcRequestClient;
-use zebra_rpc::methods::get_block_template_rpcs::get_block_template::{
-    proposal::TimeSource, proposal_block_from_template, GetBlockTemplate,
+use zebra_chain::{
+    amount::{Amount, NonNegative},
+    block::{merkle::AuthDataRoot, ChainHistoryBlockTxAuthCommitmentHash, Height},
+    parameters::NetworkUpgrade,
+    serialization::{DateTime32, ZcashDeserializeInto as _, ZcashSerialize as _},
+    transparent,
+};
+use zebra_rpc::methods::get_block_template_rpcs::{
+    get_block_template::{proposal::TimeSource, proposal_block_from_template, GetBlockTemplate},
+    types::transaction::TransactionTemplate,
 };
 
 use crate::{
+    block::{Block, BlockId},
+    checkpoint::{self, Checkpoint},
     config,
-    error::LaunchError,
-    launch, logs,
+    error::{
+        EndpointError, FundError, GenerateBlocksError, LaunchError, RpcError, SubmitBlockError,
+        WaitError,
+    },
+    funding, launch, logs,
+    mempool::{self, MempoolStream},
+    metrics::{self, Metrics},
     network::{self, Network},
-    Process,
+    producer::{BlockProducer, BlockProduction},
+    rpc::{self, RpcClient},
+    Process, MEMPOOL_TIMEOUT,
 };
 
+/// Timeout for Zcashd asynchronous wallet operations used by [`Validator::fund`].
+const OPERATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Zcashd RPC error code returned when the wallet has insufficient funds.
+const RPC_WALLET_INSUFFICIENT_FUNDS: i64 = -6;
+
 /// Zebrad default miner address. Regtest/Testnet transparent address for [Abandon Abandon .. Art] seed (entropy all zeros)
 pub const ZEBRAD_DEFAULT_MINER: &str = "tmBsTi2xWTjUdEXnuTceL7fecEQKeWaPDJd";
 
@@ -31,14 +58,22 @@ pub const ZEBRAD_DEFAULT_MINER: &str = "tmBsTi2xWTjUdEXnuTceL7fecEQKeWaPDJd";
 ///
 /// Use `zcashd_bin` and `zcash_cli_bin` to specify the paths to the binaries.
 /// If these binaries are in $PATH, `None` can be specified to run "zcashd" / "zcash-cli".
+/// Zcash-cli is only required for [`crate::validator::Zcashd::zcash_cli_command`], Zcashd is otherwise managed
+/// over JSON-RPC.
 ///
 /// If `rpc_listen_port` is `None`, a port is picked at random between 15000-25000.
 ///
 /// Use `activation_heights` to specify custom network upgrade activation heights.
 ///
 /// Use `miner_address` to specify the target address for the block rewards when blocks are generated.
+/// If `miner_address` is `None`, block rewards are paid to the Zcashd wallet, which is required for
+/// [`crate::validator::Validator::fund`].
 ///
 /// If `chain_cache` path is `None`, a new chain is launched.
+///
+/// `network` can be used for testing against cached testnet / mainnet chains where large chains are needed.
+/// `activation_heights` and `miner_address` will be ignored while not using regtest network. Zcashd does not connect
+/// to any peers so the chain will not advance past the cached chain height.
 pub struct ZcashdConfig {
     /// Zcashd binary location
     pub zcashd_bin: Option<PathBuf>,
@@ -52,6 +87,22 @@ pub struct ZcashdConfig {
     pub miner_address: Option<&'static str>,
     /// Chain cache path
     pub chain_cache: Option<PathBuf>,
+    /// Network type
+    pub network: Network,
+}
+
+impl Default for ZcashdConfig {
+    fn default() -> Self {
+        Self {
+            zcashd_bin: None,
+            zcash_cli_bin: None,
+            rpc_listen_port: None,
+            activation_heights: network::ActivationHeights::default(),
+            miner_address: None,
+            chain_cache: None,
+            network: Network::Regtest,
+        }
+    }
 }
 
 /// Zebrad configuration
@@ -69,6 +120,13 @@ pub struct ZcashdConfig {
 ///
 /// `network` can be used for testing against cached testnet / mainnet chains where large chains are needed.
 /// `activation_heights` and `miner_address` will be ignored while not using regtest network.
+///
+/// Set `metrics` to enable the Prometheus metrics endpoint on a port picked at random, see [`Zebrad::metrics`].
+/// Requires Zebrad to be built with the `prometheus` feature.
+///
+/// Use `tracing_filter` to specify the tracing filter directives, for example `"info,zebra_state=debug"`. If `None`,
+/// Zebrad's default filter is used. Set `tracing_endpoint` to enable the tracing endpoint on a port picked at random,
+/// see [`Zebrad::set_tracing_filter`]. Requires Zebrad to be built with the `filter-reload` feature.
 pub struct ZebradConfig {
     /// Zebrad binary location
     pub zebrad_bin: Option<PathBuf>,
@@ -84,6 +142,12 @@ pub struct ZebradConfig {
     pub chain_cache: Option<PathBuf>,
     /// Network type
     pub network: Network,
+    /// Enables the metrics endpoint
+    pub metrics: bool,
+    /// Tracing filter directives
+    pub tracing_filter: Option<String>,
+    /// Enables the tracing endpoint
+    pub tracing_endpoint: bool,
 }
 
 impl Default for ZebradConfig {
@@ -96,17 +160,82 @@ impl Default for ZebradConfig {
             miner_address: ZEBRAD_DEFAULT_MINER,
             chain_cache: None,
             network: Network::Regtest,
+            metrics: false,
+            tracing_filter: None,
+            tracing_endpoint: false,
         }
     }
 }
 
+/// Functionality for overriding the fields common to all validator configs, see [`crate::LocalNetBuilder`].
+pub trait ValidatorConfig: Default + Send {
+    /// Sets the validator binary location.
+    fn set_bin(&mut self, bin: PathBuf);
+
+    /// Sets the chain cache path.
+    fn set_chain_cache(&mut self, chain_cache: PathBuf);
+
+    /// Sets the local network upgrade activation heights.
+    fn set_activation_heights(&mut self, activation_heights: network::ActivationHeights);
+
+    /// Sets the miner address.
+    fn set_miner_address(&mut self, miner_address: &'static str);
+}
+
+impl ValidatorConfig for ZcashdConfig {
+    /// Sets the Zcashd binary location. The Zcash-cli binary location is not changed.
+    fn set_bin(&mut self, bin: PathBuf) {
+        self.zcashd_bin = Some(bin);
+    }
+
+    fn set_chain_cache(&mut self, chain_cache: PathBuf) {
+        self.chain_cache = Some(chain_cache);
+    }
+
+    fn set_activation_heights(&mut self, activation_heights: network::ActivationHeights) {
+        self.activation_heights = activation_heights;
+    }
+
+    fn set_miner_address(&mut self, miner_address: &'static str) {
+        self.miner_address = Some(miner_address);
+    }
+}
+
+impl ValidatorConfig for ZebradConfig {
+    fn set_bin(&mut self, bin: PathBuf) {
+        self.zebrad_bin = Some(bin);
+    }
+
+    fn set_chain_cache(&mut self, chain_cache: PathBuf) {
+        self.chain_cache = Some(chain_cache);
+    }
+
+    fn set_activation_heights(&mut self, activation_heights: network::ActivationHeights) {
+        self.activation_heights = activation_heights;
+    }
+
+    fn set_miner_address(&mut self, miner_address: &'static str) {
+        self.miner_address = miner_address;
+    }
+}
+
+/// Selects whether other mempool transactions are mined by [`Validator::mine_block_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MempoolPolicy {
+    /// Mine the given transactions along with any other transactions in the mempool
+    #[default]
+    Include,
+    /// Mine exactly the given transactions, in the given order
+    Exclude,
+}
+
 /// Functionality for validator/full-node processes.
-pub trait Validator: Sized {
+pub trait Validator: Sized + Send + Sync {
     /// Config filename
     const CONFIG_FILENAME: &str;
 
     /// Validator config struct
-    type Config;
+    type Config: ValidatorConfig;
 
     /// Launch the process.
     fn launch(
@@ -116,21 +245,381 @@ pub trait Validator: Sized {
     /// Stop the process.
     fn stop(&mut self);
 
+    /// Restart the process with the same configuration after it has been stopped with [`Self::stop`].
+    fn restart(&mut self) -> impl std::future::Future<Output = Result<(), LaunchError>> + Send;
+
+    /// Stops the process, snapshots the chain state and restarts the process.
+    ///
+    /// Pass the returned checkpoint to [`Self::rollback`] to restore the validator to this exact state, allowing
+    /// state-mutating tests to share a single launch instead of relaunching from a chain cache.
+    ///
+    /// Returns [`crate::error::LaunchError::CheckpointUnsupported`] if the network is not `Regtest`, as the process
+    /// runs directly from the testnet / mainnet chain cache.
+    fn checkpoint(
+        &mut self,
+    ) -> impl std::future::Future<Output = Result<Checkpoint, LaunchError>> + Send {
+        async move {
+            if !matches!(self.network(), Network::Regtest) {
+                return Err(LaunchError::CheckpointUnsupported(self.network()));
+            }
+            self.stop();
+            let checkpoint = checkpoint::snapshot(self.chain_dir());
+            self.restart().await?;
+
+            checkpoint.map_err(|e| LaunchError::Checkpoint(Arc::new(e)))
+        }
+    }
+
+    /// Stops the process, restores the chain state from the `checkpoint` and restarts the process.
+    ///
+    /// Returns [`crate::error::LaunchError::CheckpointUnsupported`] if the network is not `Regtest`.
+    fn rollback(
+        &mut self,
+        checkpoint: &Checkpoint,
+    ) -> impl std::future::Future<Output = Result<(), LaunchError>> + Send {
+        async move {
+            if !matches!(self.network(), Network::Regtest) {
+                return Err(LaunchError::CheckpointUnsupported(self.network()));
+            }
+            self.stop();
+            checkpoint::restore(checkpoint, self.chain_dir())
+                .map_err(|e| LaunchError::Checkpoint(Arc::new(e)))?;
+            self.restart().await
+        }
+    }
+
     /// Generate `n` blocks. This implementation should also call [`Self::poll_chain_height`] so the chain is at the
     /// correct height when this function returns.
+    ///
+    /// Returns [`crate::error::GenerateBlocksError::SubmitBlock`] if the validator does not accept a generated block.
     fn generate_blocks(
         &self,
         n: u32,
-    ) -> impl std::future::Future<Output = std::io::Result<()>> + Send;
+    ) -> impl std::future::Future<Output = Result<(), GenerateBlocksError>> + Send;
+
+    /// Sets the mock time used as the current time for block production, in seconds since the Unix epoch.
+    /// A `timestamp` of `0` resets the validator to use the system time.
+    ///
+    /// Blocks generated while a mock time is set have deterministic timestamps, allowing chain caches and compact
+    /// block fixtures to be byte-reproducible.
+    fn set_mock_time(
+        &self,
+        timestamp: u32,
+    ) -> impl std::future::Future<Output = Result<(), RpcError>> + Send;
+
+    /// Generate a single block with the given `timestamp`, in seconds since the Unix epoch.
+    ///
+    /// `timestamp` must be greater than the median time of the previous 11 blocks. The mock time set with
+    /// [`Self::set_mock_time`] is not changed.
+    fn generate_block_at(
+        &self,
+        timestamp: u32,
+    ) -> impl std::future::Future<Output = Result<(), GenerateBlocksError>> + Send;
+
+    /// Generate `n` blocks with timestamps starting at `start` and incremented by `interval` seconds for each
+    /// subsequent block.
+    ///
+    /// Returns [`crate::error::GenerateBlocksError::InvalidTimestamp`] without generating any blocks if the last
+    /// timestamp does not fit in a `u32`.
+    fn generate_blocks_with_interval(
+        &self,
+        n: u32,
+        start: u32,
+        interval: u32,
+    ) -> impl std::future::Future<Output = Result<(), GenerateBlocksError>> + Send {
+        async move {
+            n.checked_sub(1)
+                .map_or(Some(start), |last| {
+                    last.checked_mul(interval)
+                        .and_then(|offset| start.checked_add(offset))
+                })
+                .ok_or_else(|| {
+                    GenerateBlocksError::InvalidTimestamp(format!(
+                        "{n} blocks from {start} at {interval} second intervals overflow u32"
+                    ))
+                })?;
+
+            for i in 0..n {
+                self.generate_block_at(start + i * interval).await?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Generates blocks one at a time until `predicate` returns `true` for the chain height. Returns the chain height.
+    ///
+    /// `predicate` is checked before generating the first block, so no blocks are generated if it is already
+    /// satisfied. Returns [`crate::error::GenerateBlocksError::MaxBlocks`] if `predicate` is not satisfied after
+    /// generating `max_blocks` blocks.
+    fn generate_blocks_until<F>(
+        &self,
+        mut predicate: F,
+        max_blocks: u32,
+    ) -> impl std::future::Future<Output = Result<BlockHeight, GenerateBlocksError>> + Send
+    where
+        F: FnMut(BlockHeight) -> bool + Send,
+    {
+        async move {
+            let mut chain_height = self.get_chain_height().await?;
+            for _ in 0..max_blocks {
+                if predicate(chain_height) {
+                    return Ok(chain_height);
+                }
+                self.generate_blocks(1).await?;
+                chain_height = self.get_chain_height().await?;
+            }
+
+            if predicate(chain_height) {
+                Ok(chain_height)
+            } else {
+                Err(GenerateBlocksError::MaxBlocks(max_blocks))
+            }
+        }
+    }
+
+    /// Generates blocks until the first coinbase output paid to `address` can be spent by a wallet. Returns the chain
+    /// height.
+    ///
+    /// `address` should be the address block rewards are paid to. Coinbase outputs require
+    /// [`crate::funding::COINBASE_MATURITY`] confirmations and can only be paid to shielded pools once the pool's
+    /// coinbase rules are active, see [`crate::funding::coinbase_spendable_height`]. Unified addresses are assumed to
+    /// receive coinbase outputs in the most recent pool they have a receiver for.
+    fn mine_until_spendable(
+        &self,
+        address: &str,
+    ) -> impl std::future::Future<Output = Result<BlockHeight, FundError>> + Send {
+        async move {
+            let pool = funding::coinbase_pool(self.rpc_client(), address).await?;
+            let spendable_height =
+                funding::coinbase_spendable_height(self.activation_heights(), pool);
+
+            generate_blocks_to(self, spendable_height)
+                .await
+                .map_err(FundError::from)
+        }
+    }
+
+    /// Mine a single block containing the given `transactions`.
+    ///
+    /// Transactions not already in the mempool are sent to the validator first, so they must be valid for mempool
+    /// acceptance. With [`MempoolPolicy::Exclude`] the block contains exactly the given `transactions` in the given
+    /// order, allowing precise fixtures for transaction ordering and expiry. With [`MempoolPolicy::Include`] any other
+    /// transactions in the mempool are also mined.
+    ///
+    /// Returns [`crate::error::GenerateBlocksError::TransactionSelection`] if the block cannot be built with the
+    /// requested transactions or the validator does not support the `mempool_policy`.
+    fn mine_block_with(
+        &self,
+        transactions: Vec<Transaction>,
+        mempool_policy: MempoolPolicy,
+    ) -> impl std::future::Future<Output = Result<(), GenerateBlocksError>> + Send;
+
+    /// Submits the serialized `block` to the validator.
+    ///
+    /// Returns [`crate::error::GenerateBlocksError::SubmitBlock`] if the validator does not accept the block, allowing
+    /// tests to submit invalid blocks and assert on the rejection.
+    fn submit_block(
+        &self,
+        block: &[u8],
+    ) -> impl std::future::Future<Output = Result<(), GenerateBlocksError>> + Send {
+        submit_block_data(self.rpc_client(), block)
+    }
+
+    /// Starts producing blocks in the background according to the `block_production` mode, simulating a live chain.
+    ///
+    /// Block production runs in a background task until [`crate::producer::BlockProducer::stop`] is called or the
+    /// returned handle is dropped, so this must be called from within a tokio runtime.
+    fn start_block_production(&self, block_production: BlockProduction) -> BlockProducer;
 
     /// Get chain height
-    fn get_chain_height(&self) -> impl std::future::Future<Output = BlockHeight> + Send;
+    fn get_chain_height(
+        &self,
+    ) -> impl std::future::Future<Output = Result<BlockHeight, RpcError>> + Send;
 
     /// Polls chain until it reaches target height
     fn poll_chain_height(
         &self,
         target_height: BlockHeight,
-    ) -> impl std::future::Future<Output = ()> + Send;
+    ) -> impl std::future::Future<Output = Result<(), RpcError>> + Send;
+
+    /// Get the JSON-RPC client connected to the validator's RPC port.
+    fn rpc_client(&self) -> &RpcClient;
+
+    /// Get the validator's RPC port.
+    fn rpc_port(&self) -> Port;
+
+    /// Calls the RPC `method` with the given `params` and returns the `result` field of the response.
+    ///
+    /// `params` should be a JSON array of positional arguments.
+    ///
+    /// Example usage:
+    /// ```ignore (incomplete)
+    /// let block_hash = validator.rpc_call("getblockhash", serde_json::json!([1])).await?;
+    /// ```
+    fn rpc_call(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> impl std::future::Future<Output = Result<serde_json::Value, RpcError>> + Send {
+        self.rpc_client().call(method, params)
+    }
+
+    /// Get the full block for the given `block_id`.
+    fn get_block(
+        &self,
+        block_id: BlockId,
+    ) -> impl std::future::Future<Output = Result<Block, RpcError>> + Send {
+        async move {
+            let block_info = self
+                .rpc_call("getblock", serde_json::json!([block_id.to_string(), 1]))
+                .await?;
+            let height = block_info
+                .get("height")
+                .and_then(|height| height.as_u64())
+                .and_then(|height| u32::try_from(height).ok())
+                .map(BlockHeight::from_u32)
+                .ok_or_else(|| RpcError::InvalidResponse(block_info.to_string()))?;
+            let block_hash = block_info
+                .get("hash")
+                .and_then(|hash| hash.as_str())
+                .ok_or_else(|| RpcError::InvalidResponse(block_info.to_string()))?;
+
+            let block_data = self
+                .rpc_call("getblock", serde_json::json!([block_hash, 0]))
+                .await?;
+            let branch_id = self.network().branch_id(self.activation_heights(), height);
+
+            Block::read(&decode_hex(&block_data)?[..], height, branch_id)
+                .map_err(|e| RpcError::InvalidResponse(e.to_string()))
+        }
+    }
+
+    /// Get the transaction with the given `txid` from the chain or mempool.
+    fn get_raw_transaction(
+        &self,
+        txid: TxId,
+    ) -> impl std::future::Future<Output = Result<Transaction, RpcError>> + Send {
+        async move {
+            let transaction_info = self
+                .rpc_call(
+                    "getrawtransaction",
+                    serde_json::json!([txid.to_string(), 1]),
+                )
+                .await?;
+            // mempool transactions are decoded with the consensus rules of the next block
+            let height = match transaction_info
+                .get("height")
+                .and_then(|height| height.as_u64())
+                .and_then(|height| u32::try_from(height).ok())
+            {
+                Some(height) => BlockHeight::from_u32(height),
+                None => self.get_chain_height().await? + 1,
+            };
+            let transaction_data = transaction_info
+                .get("hex")
+                .ok_or_else(|| RpcError::InvalidResponse(transaction_info.to_string()))?;
+            let branch_id = self.network().branch_id(self.activation_heights(), height);
+
+            Transaction::read(&decode_hex(transaction_data)?[..], branch_id)
+                .map_err(|e| RpcError::InvalidResponse(e.to_string()))
+        }
+    }
+
+    /// Get the txids of all transactions in the mempool.
+    fn get_raw_mempool(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<TxId>, RpcError>> + Send {
+        mempool::get_raw_mempool(self.rpc_client())
+    }
+
+    /// Get the number of transactions in the mempool.
+    fn mempool_size(&self) -> impl std::future::Future<Output = Result<usize, RpcError>> + Send {
+        async move { Ok(self.get_raw_mempool().await?.len()) }
+    }
+
+    /// Waits until all `txids` are in the mempool.
+    ///
+    /// Returns [`crate::error::WaitError::Timeout`] if any of the transactions have not arrived within `timeout`.
+    fn wait_for_mempool_txids(
+        &self,
+        txids: &[TxId],
+        timeout: std::time::Duration,
+    ) -> impl std::future::Future<Output = Result<(), WaitError>> + Send {
+        async move {
+            let start = std::time::Instant::now();
+            loop {
+                let mempool_txids = self.get_raw_mempool().await?;
+                let missing_txids: Vec<String> = txids
+                    .iter()
+                    .filter(|txid| !mempool_txids.contains(txid))
+                    .map(|txid| txid.to_string())
+                    .collect();
+                if missing_txids.is_empty() {
+                    return Ok(());
+                }
+                if start.elapsed() >= timeout {
+                    return Err(WaitError::Timeout {
+                        timeout,
+                        waiting_for: format!("txids in mempool: {}", missing_txids.join(", ")),
+                    });
+                }
+
+                tokio::time::sleep(mempool::POLL_INTERVAL).await;
+            }
+        }
+    }
+
+    /// Returns a stream of changes to the mempool, polled every `poll_interval`.
+    ///
+    /// Transactions already in the mempool are yielded as [`crate::mempool::MempoolChange::Added`] on the first
+    /// poll. Polling runs in a background task which stops when the stream is dropped, so this must be called from
+    /// within a tokio runtime.
+    fn mempool_changes(&self, poll_interval: std::time::Duration) -> MempoolStream {
+        mempool::watch(self.rpc_client().clone(), poll_interval)
+    }
+
+    /// Sends the serialized `transaction` to the validator's mempool. Returns the txid.
+    fn send_raw_transaction(
+        &self,
+        transaction: &[u8],
+    ) -> impl std::future::Future<Output = Result<TxId, RpcError>> + Send {
+        let transaction_data = hex::encode(transaction);
+        async move {
+            let txid = self
+                .rpc_call("sendrawtransaction", serde_json::json!([transaction_data]))
+                .await?;
+
+            rpc::decode_hash(&txid).map(TxId::from_bytes)
+        }
+    }
+
+    /// Get the hash of the block at the tip of the best chain.
+    fn get_best_block_hash(
+        &self,
+    ) -> impl std::future::Future<Output = Result<BlockHash, RpcError>> + Send {
+        async move {
+            let block_hash = self
+                .rpc_call("getbestblockhash", serde_json::json!([]))
+                .await?;
+
+            rpc::decode_hash(&block_hash).map(BlockHash)
+        }
+    }
+
+    /// Sends `amount` to the `pool` receiver of `address` from the validator's mining rewards and mines a block
+    /// confirming the transaction. Returns the txid of the funding transaction.
+    ///
+    /// Allows funding any wallet under test without building and syncing a light client faucet. Not all pools are
+    /// supported by every validator: Zcashd funds all pools but must be launched without a `miner_address`, while
+    /// Zebrad only funds the Orchard pool and requires the `zebrad-funding` feature. Returns
+    /// [`crate::error::FundError::UnsupportedPool`] if the validator cannot fund the `pool`.
+    fn fund(
+        &self,
+        address: &str,
+        amount: Zatoshis,
+        pool: PoolType,
+    ) -> impl std::future::Future<Output = Result<TxId, FundError>> + Send;
 
     /// Get temporary config directory.
     fn config_dir(&self) -> &TempDir;
@@ -141,14 +630,28 @@ pub trait Validator: Sized {
     /// Get temporary data directory.
     fn data_dir(&self) -> &TempDir;
 
+    /// Get the directory the process loads the chain state from. This is the data directory unless the process
+    /// was launched directly from a testnet / mainnet chain cache.
+    fn chain_dir(&self) -> &Path;
+
     /// Returns path to config file.
     fn config_path(&self) -> PathBuf {
         self.config_dir().path().join(Self::CONFIG_FILENAME)
     }
 
+    /// Returns path to the zcashd config file specifying the validator's RPC port, as required by Lightwalletd.
+    ///
+    /// Validators which are not Zcashd must write this file to the config directory on launch.
+    fn zcashd_conf_path(&self) -> PathBuf {
+        self.config_dir().path().join(config::ZCASHD_FILENAME)
+    }
+
     /// Network type
     fn network(&self) -> Network;
 
+    /// Local network upgrade activation heights
+    fn activation_heights(&self) -> &network::ActivationHeights;
+
     /// Caches chain. This stops the zcashd process.
     fn cache_chain(&mut self, chain_cache: PathBuf) -> std::process::Output {
         if chain_cache.exists() {
@@ -210,14 +713,31 @@ pub struct Zcashd {
     zcash_cli_bin: Option<PathBuf>,
     /// Network upgrade activation heights
     activation_heights: network::ActivationHeights,
+    /// RPC client
+    #[getset(skip)]
+    rpc_client: RpcClient,
+    /// Network type
+    network: Network,
+    /// Chain state directory
+    #[getset(skip)]
+    chain_dir: PathBuf,
+    /// Mock time set with [`Validator::set_mock_time`], restored after [`Validator::generate_block_at`]
+    #[getset(skip)]
+    mock_time: Mutex<u32>,
+    /// Launch command, kept for restarting the process
+    #[getset(skip)]
+    command: std::process::Command,
 }
 
 impl Zcashd {
     /// Runs a Zcash-cli command with the given `args`.
     ///
-    /// Example usage for generating blocks in Zcashd local net:
+    /// Zcashd is managed over JSON-RPC so Zcash-cli is not required. This is provided as a passthrough for running
+    /// Zcash-cli directly against the Zcashd process.
+    ///
+    /// Example usage for getting blockchain info in Zcashd local net:
     /// ```ignore (incomplete)
-    /// self.zcash_cli_command(&["generate", "1"]);
+    /// self.zcash_cli_command(&["getblockchaininfo"]);
     /// ```
     pub fn zcash_cli_command(&self, args: &[&str]) -> std::io::Result<std::process::Output> {
         let mut command = match &self.zcash_cli_bin {
@@ -228,6 +748,138 @@ impl Zcashd {
         command.arg(format!("-conf={}", self.config_path().to_str().unwrap()));
         command.args(args).output()
     }
+
+    /// Spawns the Zcashd process from the launch `command` and waits for it to finish loading.
+    fn spawn(
+        command: &mut std::process::Command,
+        logs_dir: &TempDir,
+    ) -> Result<Child, LaunchError> {
+        let mut handle = command.spawn().map_err(|e| LaunchError::Spawn {
+            process_name: Process::Zcashd.to_string(),
+            source: Arc::new(e),
+        })?;
+
+        logs::write_logs(&mut handle, logs_dir);
+        launch::wait(
+            Process::Zcashd,
+            &mut handle,
+            logs_dir,
+            None,
+            &["init message: Done loading"],
+            &["Error:"],
+            &[],
+        )?;
+
+        Ok(handle)
+    }
+
+    /// Returns the unified address of the wallet account that mining rewards are shielded to for funding, creating
+    /// the account on first use.
+    async fn funding_address(&self) -> Result<String, RpcError> {
+        let accounts = self
+            .rpc_call("z_listaccounts", serde_json::json!([]))
+            .await?;
+        if let Some(address) = accounts
+            .get(0)
+            .and_then(|account| account.get("addresses"))
+            .and_then(|addresses| addresses.get(0))
+            .and_then(|address| address.get("ua"))
+            .and_then(|address| address.as_str())
+        {
+            return Ok(address.to_string());
+        }
+
+        let account = self
+            .rpc_call("z_getnewaccount", serde_json::json!([]))
+            .await?;
+        let account = account
+            .get("account")
+            .cloned()
+            .ok_or_else(|| RpcError::InvalidResponse(account.to_string()))?;
+        let address = self
+            .rpc_call("z_getaddressforaccount", serde_json::json!([account]))
+            .await?;
+
+        address
+            .get("address")
+            .and_then(|address| address.as_str())
+            .map(|address| address.to_string())
+            .ok_or_else(|| RpcError::InvalidResponse(address.to_string()))
+    }
+
+    /// Shields the mature coinbase outputs of the wallet to the `funding_address` and mines a block confirming the
+    /// shielding transaction.
+    ///
+    /// Blocks are mined until the first wallet coinbase output is spendable. If there are no coinbase outputs left to
+    /// shield, previously shielded funds are used.
+    async fn shield_coinbase(&self, funding_address: &str) -> Result<(), FundError> {
+        let spendable_height =
+            funding::coinbase_spendable_height(&self.activation_heights, PoolType::Transparent);
+        generate_blocks_to(self, spendable_height).await?;
+
+        let response = self
+            .rpc_call(
+                "z_shieldcoinbase",
+                serde_json::json!([
+                    "*",
+                    funding_address,
+                    null,
+                    null,
+                    null,
+                    "AllowRevealedSenders"
+                ]),
+            )
+            .await;
+        let operation_id = match response {
+            Ok(response) => response
+                .get("opid")
+                .and_then(|operation_id| operation_id.as_str())
+                .ok_or_else(|| RpcError::InvalidResponse(response.to_string()))?
+                .to_string(),
+            Err(RpcError::Response { code, .. }) if code == RPC_WALLET_INSUFFICIENT_FUNDS => {
+                return Ok(())
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        self.wait_for_operation(&operation_id).await?;
+        self.generate_blocks(1).await?;
+
+        Ok(())
+    }
+
+    /// Waits for the asynchronous wallet operation with the given `operation_id` to complete. Returns the txid of
+    /// the transaction created by the operation.
+    async fn wait_for_operation(&self, operation_id: &str) -> Result<TxId, FundError> {
+        let start = std::time::Instant::now();
+        loop {
+            let results = self
+                .rpc_call("z_getoperationresult", serde_json::json!([[operation_id]]))
+                .await?;
+            if let Some(result) = results.as_array().and_then(|results| results.first()) {
+                return match result.get("status").and_then(|status| status.as_str()) {
+                    Some("success") => Ok(TxId::from_bytes(rpc::decode_hash(
+                        &result["result"]["txid"],
+                    )?)),
+                    _ => Err(FundError::Operation(
+                        result["error"]["message"]
+                            .as_str()
+                            .map(|message| message.to_string())
+                            .unwrap_or_else(|| result.to_string()),
+                    )),
+                };
+            }
+            if start.elapsed() >= OPERATION_TIMEOUT {
+                return Err(WaitError::Timeout {
+                    timeout: OPERATION_TIMEOUT,
+                    waiting_for: format!("wallet operation {operation_id}"),
+                }
+                .into());
+            }
+
+            tokio::time::sleep(mempool::POLL_INTERVAL).await;
+        }
+    }
 }
 
 impl Validator for Zcashd {
@@ -239,17 +891,24 @@ impl Validator for Zcashd {
         let logs_dir = tempfile::tempdir().unwrap();
         let data_dir = tempfile::tempdir().unwrap();
 
-        if let Some(cache) = config.chain_cache.clone() {
-            Self::load_chain(cache, data_dir.path().to_path_buf(), Network::Regtest);
+        if !matches!(config.network, Network::Regtest) && config.chain_cache.is_none() {
+            return Err(LaunchError::MissingChainCache(config.network));
         }
 
-        let port = network::pick_unused_port(config.rpc_listen_port);
+        let chain_dir = if let Some(cache) = config.chain_cache.clone() {
+            Self::load_chain(cache, data_dir.path().to_path_buf(), config.network)
+        } else {
+            data_dir.path().to_path_buf()
+        };
+
+        let port = network::pick_unused_port(config.rpc_listen_port)?;
         let config_dir = tempfile::tempdir().unwrap();
         let config_file_path = config::zcashd(
             config_dir.path(),
             port,
             &config.activation_heights,
             config.miner_address,
+            config.network,
         )
         .unwrap();
 
@@ -267,7 +926,7 @@ impl Validator for Zcashd {
                 .as_str(),
                 format!(
                     "--datadir={}",
-                    data_dir.path().to_str().expect("should be valid UTF-8")
+                    chain_dir.to_str().expect("should be valid UTF-8")
                 )
                 .as_str(),
                 "-debug=1",
@@ -275,18 +934,7 @@ impl Validator for Zcashd {
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
 
-        let mut handle = command.spawn().unwrap();
-
-        logs::write_logs(&mut handle, &logs_dir);
-        launch::wait(
-            Process::Zcashd,
-            &mut handle,
-            &logs_dir,
-            None,
-            &["init message: Done loading"],
-            &["Error:"],
-            &[],
-        )?;
+        let handle = Self::spawn(&mut command, &logs_dir)?;
 
         let zcashd = Zcashd {
             handle,
@@ -296,18 +944,27 @@ impl Validator for Zcashd {
             data_dir,
             zcash_cli_bin: config.zcash_cli_bin,
             activation_heights: config.activation_heights,
+            rpc_client: RpcClient::new(port)
+                .with_basic_auth(config::RPC_USER, config::RPC_PASSWORD),
+            network: config.network,
+            chain_dir,
+            mock_time: Mutex::new(0),
+            command,
         };
 
         if config.chain_cache.is_none() {
             // generate genesis block
-            zcashd.generate_blocks(1).await.unwrap();
+            zcashd
+                .generate_blocks(1)
+                .await
+                .map_err(|e| LaunchError::GenesisBlock(Arc::new(e)))?;
         }
 
         Ok(zcashd)
     }
 
     fn stop(&mut self) {
-        match self.zcash_cli_command(&["stop"]) {
+        match self.rpc_client.call_blocking("stop", serde_json::json!([])) {
             Ok(_) => {
                 if let Err(e) = self.handle.wait() {
                     tracing::error!("zcashd cannot be awaited: {e}")
@@ -317,34 +974,145 @@ impl Validator for Zcashd {
             }
             Err(e) => {
                 tracing::error!(
-                    "Can't stop zcashd from zcash-cli: {e}\n\
+                    "Can't stop zcashd from RPC: {e}\n\
                     Sending SIGKILL to zcashd process."
                 );
                 if let Err(e) = self.handle.kill() {
                     tracing::warn!("zcashd has already terminated: {e}")
                 };
+                let _ = self.handle.wait();
             }
         }
     }
 
-    async fn generate_blocks(&self, n: u32) -> std::io::Result<()> {
-        let chain_height = self.get_chain_height().await;
-        self.zcash_cli_command(&["generate", &n.to_string()])?;
-        self.poll_chain_height(chain_height + n).await;
+    async fn restart(&mut self) -> Result<(), LaunchError> {
+        self.handle = Self::spawn(&mut self.command, &self.logs_dir)?;
 
         Ok(())
     }
 
-    async fn get_chain_height(&self) -> BlockHeight {
-        let output = self.zcash_cli_command(&["getchaintips"]).unwrap();
-        let stdout_json = json::parse(&String::from_utf8_lossy(&output.stdout)).unwrap();
-        BlockHeight::from_u32(stdout_json[0]["height"].as_u32().unwrap())
+    async fn generate_blocks(&self, n: u32) -> Result<(), GenerateBlocksError> {
+        let chain_height = self.get_chain_height().await?;
+        self.rpc_call("generate", serde_json::json!([n])).await?;
+        self.poll_chain_height(chain_height + n).await?;
+
+        Ok(())
+    }
+
+    async fn set_mock_time(&self, timestamp: u32) -> Result<(), RpcError> {
+        self.rpc_call("setmocktime", serde_json::json!([timestamp]))
+            .await?;
+        *self.mock_time.lock().unwrap() = timestamp;
+
+        Ok(())
+    }
+
+    /// The mock time is set to `timestamp` while the block is mined and then restored to the mock time set with
+    /// [`Self::set_mock_time`], or the system time if none is set.
+    async fn generate_block_at(&self, timestamp: u32) -> Result<(), GenerateBlocksError> {
+        let mock_time = *self.mock_time.lock().unwrap();
+        self.rpc_call("setmocktime", serde_json::json!([timestamp]))
+            .await?;
+        let result = self.generate_blocks(1).await;
+        self.rpc_call("setmocktime", serde_json::json!([mock_time]))
+            .await?;
+
+        result
+    }
+
+    /// Zcashd mines all transactions in the mempool and requires a valid proof-of-work, so blocks can't be built from
+    /// a modified template. [`MempoolPolicy::Exclude`] is unsupported and returns an error before any transactions are
+    /// sent to the mempool.
+    async fn mine_block_with(
+        &self,
+        transactions: Vec<Transaction>,
+        mempool_policy: MempoolPolicy,
+    ) -> Result<(), GenerateBlocksError> {
+        if mempool_policy == MempoolPolicy::Exclude {
+            return Err(GenerateBlocksError::TransactionSelection(
+                "zcashd does not support excluding mempool transactions".to_string(),
+            ));
+        }
+
+        send_to_mempool(self, &transactions).await?;
+        self.generate_blocks(1).await
     }
 
-    async fn poll_chain_height(&self, target_height: BlockHeight) {
-        while self.get_chain_height().await < target_height {
+    fn start_block_production(&self, block_production: BlockProduction) -> BlockProducer {
+        let rpc_client = self.rpc_client.clone();
+        BlockProducer::spawn(self.rpc_client.clone(), block_production, move || {
+            let rpc_client = rpc_client.clone();
+            async move {
+                rpc_client.call("generate", serde_json::json!([1])).await?;
+
+                Ok(())
+            }
+        })
+    }
+
+    /// Mining rewards are funded from the Zcashd wallet so Zcashd must be launched without a `miner_address`. The
+    /// mature coinbase outputs of the wallet are shielded to a wallet account which sends the funds with `z_sendmany`.
+    /// All pools are supported.
+    async fn fund(
+        &self,
+        address: &str,
+        amount: Zatoshis,
+        pool: PoolType,
+    ) -> Result<TxId, FundError> {
+        let receiver = funding::receiver_for_pool(&self.rpc_client, address, pool).await?;
+        let funding_address = self.funding_address().await?;
+        self.shield_coinbase(&funding_address).await?;
+
+        let response = self
+            .rpc_call(
+                "z_sendmany",
+                serde_json::json!([
+                    funding_address,
+                    [{
+                        "address": receiver,
+                        "amount": funding::zec_amount(amount),
+                    }],
+                    1,
+                    null,
+                    "AllowRevealedRecipients"
+                ]),
+            )
+            .await?;
+        let operation_id = response
+            .as_str()
+            .ok_or_else(|| RpcError::InvalidResponse(response.to_string()))?;
+        let txid = self.wait_for_operation(operation_id).await?;
+        self.generate_blocks(1).await?;
+
+        Ok(txid)
+    }
+
+    async fn get_chain_height(&self) -> Result<BlockHeight, RpcError> {
+        let block_count = self
+            .rpc_call("getblockcount", serde_json::json!([]))
+            .await?;
+
+        block_count
+            .as_u64()
+            .and_then(|height| u32::try_from(height).ok())
+            .map(BlockHeight::from_u32)
+            .ok_or_else(|| RpcError::InvalidResponse(block_count.to_string()))
+    }
+
+    async fn poll_chain_height(&self, target_height: BlockHeight) -> Result<(), RpcError> {
+        while self.get_chain_height().await? < target_height {
             std::thread::sleep(std::time::Duration::from_millis(100));
         }
+
+        Ok(())
+    }
+
+    fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+
+    fn rpc_port(&self) -> Port {
+        self.port
     }
 
     fn config_dir(&self) -> &TempDir {
@@ -359,27 +1127,43 @@ impl Validator for Zcashd {
         &self.data_dir
     }
 
+    fn chain_dir(&self) -> &Path {
+        &self.chain_dir
+    }
+
     fn network(&self) -> Network {
-        unimplemented!();
+        self.network
+    }
+
+    fn activation_heights(&self) -> &network::ActivationHeights {
+        &self.activation_heights
     }
 
     fn load_chain(
         chain_cache: PathBuf,
         validator_data_dir: PathBuf,
-        _validator_network: Network,
+        validator_network: Network,
     ) -> PathBuf {
-        let regtest_dir = chain_cache.clone().join("regtest");
-        if !regtest_dir.exists() {
-            panic!("regtest directory not found!");
+        let network_dir = match validator_network {
+            Network::Regtest => chain_cache.clone().join("regtest"),
+            Network::Testnet => chain_cache.clone().join("testnet3"),
+            Network::Mainnet => chain_cache.clone().join("blocks"),
+        };
+        if !network_dir.exists() {
+            panic!("{} directory not found!", network_dir.display());
         }
 
-        std::process::Command::new("cp")
-            .arg("-r")
-            .arg(regtest_dir)
-            .arg(validator_data_dir)
-            .output()
-            .unwrap();
-        chain_cache
+        if matches!(validator_network, Network::Regtest) {
+            std::process::Command::new("cp")
+                .arg("-r")
+                .arg(network_dir)
+                .arg(validator_data_dir.clone())
+                .output()
+                .unwrap();
+            validator_data_dir
+        } else {
+            chain_cache
+        }
     }
 }
 
@@ -403,6 +1187,14 @@ pub struct Zebrad {
     #[getset(skip)]
     #[getset(get_copy = "pub")]
     rpc_listen_port: Port,
+    /// Metrics listen port, `None` if the metrics endpoint is disabled
+    #[getset(skip)]
+    #[getset(get_copy = "pub")]
+    metrics_listen_port: Option<Port>,
+    /// Tracing endpoint listen port, `None` if the tracing endpoint is disabled
+    #[getset(skip)]
+    #[getset(get_copy = "pub")]
+    tracing_listen_port: Option<Port>,
     /// Config directory
     config_dir: TempDir,
     /// Logs directory
@@ -411,10 +1203,186 @@ pub struct Zebrad {
     data_dir: TempDir,
     /// Network upgrade activation heights
     activation_heights: network::ActivationHeights,
-    /// RPC request client
-    client: RpcRequestClient,
+    /// RPC client
+    #[getset(skip)]
+    rpc_client: RpcClient,
     /// Network type
     network: Network,
+    /// Zebra-chain network parameters, for selecting the network upgrade of block proposals
+    #[getset(skip)]
+    zebra_network: zebra_chain::parameters::Network,
+    /// Miner address
+    #[getset(skip)]
+    #[getset(get_copy = "pub")]
+    miner_address: &'static str,
+    /// Mock time used for block template timestamps
+    #[getset(skip)]
+    mock_time: Mutex<Option<DateTime32>>,
+    /// Chain state directory
+    #[getset(skip)]
+    chain_dir: PathBuf,
+    /// Launch command, kept for restarting the process
+    #[getset(skip)]
+    command: std::process::Command,
+}
+
+impl Zebrad {
+    /// Spawns the Zebrad process from the launch `command` and waits for it to start syncing.
+    fn spawn(
+        command: &mut std::process::Command,
+        logs_dir: &TempDir,
+    ) -> Result<Child, LaunchError> {
+        let mut handle = command.spawn().map_err(|e| LaunchError::Spawn {
+            process_name: Process::Zebrad.to_string(),
+            source: Arc::new(e),
+        })?;
+
+        logs::write_logs(&mut handle, logs_dir);
+        launch::wait(
+            Process::Zebrad,
+            &mut handle,
+            logs_dir,
+            None,
+            &["Release always valid in Testnet", "starting sync"],
+            &["error:"],
+            &["error: \"failed to lookup address information: Temporary failure in name resolution\""]
+        )?;
+        std::thread::sleep(std::time::Duration::from_secs(5));
+
+        Ok(handle)
+    }
+
+    /// Generates and submits a single block with a timestamp from `time_source`.
+    async fn generate_block(&self, time_source: TimeSource) -> Result<(), GenerateBlocksError> {
+        generate_zebrad_block(&self.rpc_client, &self.zebra_network, time_source).await
+    }
+
+    /// Gets a block template for the next block.
+    async fn get_block_template(&self) -> Result<GetBlockTemplate, GenerateBlocksError> {
+        get_zebrad_block_template(&self.rpc_client).await
+    }
+
+    /// Builds a block from the `block_template` with a timestamp from `time_source` and submits it.
+    async fn submit_block_template(
+        &self,
+        block_template: &GetBlockTemplate,
+        time_source: TimeSource,
+    ) -> Result<(), GenerateBlocksError> {
+        submit_zebrad_block_template(
+            &self.rpc_client,
+            &self.zebra_network,
+            block_template,
+            time_source,
+        )
+        .await
+    }
+
+    /// Scrapes the metrics endpoint.
+    ///
+    /// Returns [`crate::error::EndpointError::Disabled`] if Zebrad was launched without `metrics` enabled.
+    pub async fn metrics(&self) -> Result<Metrics, EndpointError> {
+        let port = self
+            .metrics_listen_port
+            .ok_or(EndpointError::Disabled("metrics"))?;
+
+        metrics::scrape(port).await
+    }
+
+    /// Gets the current tracing filter from the tracing endpoint.
+    ///
+    /// Returns [`crate::error::EndpointError::Disabled`] if Zebrad was launched without `tracing_endpoint` enabled.
+    pub async fn tracing_filter(&self) -> Result<String, EndpointError> {
+        let response = reqwest::get(self.tracing_filter_url()?).await?;
+
+        metrics::response_text(response).await
+    }
+
+    /// Sets the tracing filter through the tracing endpoint, changing the log verbosity without restarting Zebrad.
+    ///
+    /// Returns [`crate::error::EndpointError::Disabled`] if Zebrad was launched without `tracing_endpoint` enabled.
+    pub async fn set_tracing_filter(&self, filter: &str) -> Result<(), EndpointError> {
+        let response = reqwest::Client::new()
+            .post(self.tracing_filter_url()?)
+            .body(filter.to_string())
+            .send()
+            .await?;
+
+        metrics::response_text(response).await.map(|_| ())
+    }
+
+    /// Returns the URL of the tracing endpoint filter.
+    fn tracing_filter_url(&self) -> Result<String, EndpointError> {
+        let port = self
+            .tracing_listen_port
+            .ok_or(EndpointError::Disabled("tracing"))?;
+
+        Ok(format!("{}:{}/filter", network::LOCALHOST_IPV4, port))
+    }
+
+    /// Returns the source of block timestamps, using the mock time if set.
+    fn time_source(&self) -> TimeSource {
+        match *self.mock_time.lock().unwrap() {
+            Some(mock_time) => TimeSource::Clamped(mock_time),
+            None => TimeSource::default(),
+        }
+    }
+}
+
+#[cfg(feature = "zebrad-funding")]
+impl Zebrad {
+    /// Mines a block paying the miner reward to the services-managed funding address instead of the miner address.
+    /// Returns the outpoint of the funding coinbase output along with the output.
+    async fn mine_funding_coinbase(&self) -> Result<(OutPoint, TxOut), FundError> {
+        let chain_height = self.get_chain_height().await?;
+
+        let mut block_template = self.get_block_template().await?;
+        let mut coinbase = decode_coinbase(&block_template)?;
+        let (index, miner_output) = miner_output(&mut coinbase, self.miner_address)?;
+        miner_output.lock_script = transparent::Script::new(&funding::zebrad::funding_script().0);
+        let value = u64::try_from(i64::from(miner_output.value))
+            .map_err(|e| FundError::Transaction(e.to_string()))?;
+        replace_coinbase(&mut block_template, &coinbase)?;
+
+        self.submit_block_template(&block_template, self.time_source())
+            .await?;
+        self.poll_chain_height(chain_height + 1).await?;
+
+        let outpoint = OutPoint::new(
+            coinbase.hash().0,
+            u32::try_from(index).expect("coinbase output index should fit in a u32"),
+        );
+
+        Ok((outpoint, funding::zebrad::funding_coin(value)?))
+    }
+
+    /// Funds the Orchard receiver of `address` by spending a matured funding coinbase output, see [`Validator::fund`].
+    async fn fund_orchard(&self, address: &str, amount: Zatoshis) -> Result<TxId, FundError> {
+        let receiver = funding::receiver_for_pool(
+            &self.rpc_client,
+            address,
+            PoolType::Shielded(ShieldedProtocol::Orchard),
+        )
+        .await?;
+
+        let coinbase_height = self.get_chain_height().await? + 1;
+        let (outpoint, coin) = self.mine_funding_coinbase().await?;
+        generate_blocks_to(self, coinbase_height + funding::COINBASE_MATURITY).await?;
+
+        let (txid, transaction_data) = funding::zebrad::build_orchard_spend(
+            self.activation_heights,
+            self.get_chain_height().await? + 1,
+            outpoint,
+            coin,
+            &receiver,
+            amount,
+        )?;
+        self.send_raw_transaction(&transaction_data).await?;
+        self.wait_for_mempool_txids(&[txid], MEMPOOL_TIMEOUT)
+            .await?;
+        self.generate_blocks(1).await?;
+
+        Ok(txid)
+    }
 }
 
 impl Validator for Zebrad {
@@ -427,24 +1395,34 @@ impl Validator for Zebrad {
         let data_dir = tempfile::tempdir().unwrap();
 
         if !matches!(config.network, Network::Regtest) && config.chain_cache.is_none() {
-            panic!("chain cache must be specified when not using a regtest network!")
+            return Err(LaunchError::MissingChainCache(config.network));
         }
 
-        let cache_dir = if let Some(cache) = config.chain_cache.clone() {
-            Self::load_chain(cache.clone(), data_dir.path().to_path_buf(), config.network);
-            cache
+        let chain_dir = if let Some(cache) = config.chain_cache.clone() {
+            Self::load_chain(cache, data_dir.path().to_path_buf(), config.network)
         } else {
             data_dir.path().to_path_buf()
         };
 
-        let network_listen_port = network::pick_unused_port(config.network_listen_port);
-        let rpc_listen_port = network::pick_unused_port(config.rpc_listen_port);
+        let network_listen_port = network::pick_unused_port(config.network_listen_port)?;
+        let rpc_listen_port = network::pick_unused_port(config.rpc_listen_port)?;
+        let metrics_listen_port = config
+            .metrics
+            .then(|| network::pick_unused_port(None))
+            .transpose()?;
+        let tracing_listen_port = config
+            .tracing_endpoint
+            .then(|| network::pick_unused_port(None))
+            .transpose()?;
         let config_dir = tempfile::tempdir().unwrap();
         let config_file_path = config::zebrad(
             config_dir.path().to_path_buf(),
-            cache_dir,
+            chain_dir.clone(),
             network_listen_port,
             rpc_listen_port,
+            metrics_listen_port,
+            config.tracing_filter.as_deref(),
+            tracing_listen_port,
             &config.activation_heights,
             config.miner_address,
             config.network,
@@ -456,6 +1434,7 @@ impl Validator for Zebrad {
             rpc_listen_port,
             &config.activation_heights,
             None,
+            config.network,
         )
         .unwrap();
 
@@ -476,38 +1455,33 @@ impl Validator for Zebrad {
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
 
-        let mut handle = command.spawn().unwrap();
-
-        logs::write_logs(&mut handle, &logs_dir);
-        launch::wait(
-            Process::Zebrad,
-            &mut handle,
-            &logs_dir,
-            None,
-            &["Release always valid in Testnet", "starting sync"],
-            &["error:"],
-            &["error: \"failed to lookup address information: Temporary failure in name resolution\""]
-        )?;
-        std::thread::sleep(std::time::Duration::from_secs(5));
-
-        let rpc_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), rpc_listen_port);
-        let client = zebra_node_services::rpc_client::RpcRequestClient::new(rpc_address);
+        let handle = Self::spawn(&mut command, &logs_dir)?;
 
         let zebrad = Zebrad {
             handle,
             network_listen_port,
             rpc_listen_port,
+            metrics_listen_port,
+            tracing_listen_port,
             config_dir,
             logs_dir,
             data_dir,
             activation_heights: config.activation_heights,
-            client,
+            rpc_client: RpcClient::new(rpc_listen_port),
             network: config.network,
+            zebra_network: config.network.zebra_network(&config.activation_heights),
+            miner_address: config.miner_address,
+            mock_time: Mutex::new(None),
+            chain_dir,
+            command,
         };
 
         if config.chain_cache.is_none() && matches!(config.network, Network::Regtest) {
             // generate genesis block
-            zebrad.generate_blocks(1).await.unwrap();
+            zebrad
+                .generate_blocks(1)
+                .await
+                .map_err(|e| LaunchError::GenesisBlock(Arc::new(e)))?;
         }
         std::thread::sleep(std::time::Duration::from_secs(5));
 
@@ -515,74 +1489,148 @@ impl Validator for Zebrad {
     }
 
     fn stop(&mut self) {
-        self.handle.kill().expect("zebrad couldn't be killed")
+        self.handle.kill().expect("zebrad couldn't be killed");
+        // wait for the process to exit and release the state database
+        let _ = self.handle.wait();
     }
 
-    async fn generate_blocks(&self, n: u32) -> std::io::Result<()> {
-        let chain_height = self.get_chain_height().await;
+    async fn restart(&mut self) -> Result<(), LaunchError> {
+        self.handle = Self::spawn(&mut self.command, &self.logs_dir)?;
 
+        Ok(())
+    }
+
+    async fn generate_blocks(&self, n: u32) -> Result<(), GenerateBlocksError> {
+        let chain_height = self.get_chain_height().await?;
+
+        let time_source = self.time_source();
         for _ in 0..n {
-            let block_template: GetBlockTemplate = self
-                .client
-                .json_result_from_call("getblocktemplate", "[]".to_string())
-                .await
-                .expect("response should be success output with a serialized `GetBlockTemplate`");
+            self.generate_block(time_source).await?;
+        }
+        self.poll_chain_height(chain_height + n).await?;
 
-            let network_upgrade = if block_template.height < self.activation_heights().nu5.into() {
-                NetworkUpgrade::Canopy
-            } else if block_template.height < self.activation_heights().nu6.into() {
-                NetworkUpgrade::Nu5
-            } else {
-                NetworkUpgrade::Nu6
-            };
+        Ok(())
+    }
 
-            let block_data = hex::encode(
-                proposal_block_from_template(
-                    &block_template,
-                    TimeSource::default(),
-                    network_upgrade,
-                )
-                .unwrap()
-                .zcash_serialize_to_vec()
-                .unwrap(),
-            );
-
-            let submit_block_response = self
-                .client
-                .text_from_call("submitblock", format!(r#"["{block_data}"]"#))
-                .await
-                .unwrap();
+    /// Zebrad has no mock time RPC so the mock time is only applied to the timestamps of blocks generated by
+    /// [`Self::generate_blocks`]. Timestamps are clamped to the valid range of the block template.
+    async fn set_mock_time(&self, timestamp: u32) -> Result<(), RpcError> {
+        *self.mock_time.lock().unwrap() = (timestamp != 0).then(|| DateTime32::from(timestamp));
 
-            if !submit_block_response.contains(r#""result":null"#) {
-                dbg!(&submit_block_response);
-                panic!("failed to submit block!");
-            }
+        Ok(())
+    }
+
+    /// Returns [`crate::error::GenerateBlocksError::InvalidTimestamp`] without generating a block if the `timestamp`
+    /// is outside the valid range of the block template.
+    async fn generate_block_at(&self, timestamp: u32) -> Result<(), GenerateBlocksError> {
+        let chain_height = self.get_chain_height().await?;
+
+        let block_template = self.get_block_template().await?;
+        let time = DateTime32::from(timestamp);
+        if time < block_template.min_time || time > block_template.max_time {
+            return Err(GenerateBlocksError::InvalidTimestamp(format!(
+                "{timestamp} is outside the block template time range {} to {}",
+                block_template.min_time.timestamp(),
+                block_template.max_time.timestamp()
+            )));
         }
-        self.poll_chain_height(chain_height + n).await;
+        self.submit_block_template(&block_template, TimeSource::Time(time))
+            .await?;
+        self.poll_chain_height(chain_height + 1).await?;
 
         Ok(())
     }
 
-    async fn get_chain_height(&self) -> BlockHeight {
-        let response: serde_json::Value = self
-            .client
-            .json_result_from_call("getblockchaininfo", "[]".to_string())
-            .await
-            .unwrap();
+    async fn mine_block_with(
+        &self,
+        transactions: Vec<Transaction>,
+        mempool_policy: MempoolPolicy,
+    ) -> Result<(), GenerateBlocksError> {
+        let txids = send_to_mempool(self, &transactions).await?;
+        let chain_height = self.get_chain_height().await?;
+
+        let mut block_template = self.get_block_template().await?;
+        select_transactions(
+            &mut block_template,
+            &txids,
+            mempool_policy,
+            self.miner_address,
+        )?;
+        self.submit_block_template(&block_template, self.time_source())
+            .await?;
+        self.poll_chain_height(chain_height + 1).await?;
+
+        Ok(())
+    }
+
+    /// Blocks are built from block templates in the background task, with timestamps from the mock time set when
+    /// block production is started.
+    fn start_block_production(&self, block_production: BlockProduction) -> BlockProducer {
+        let rpc_client = self.rpc_client.clone();
+        let zebra_network = self.zebra_network.clone();
+        let time_source = self.time_source();
+        BlockProducer::spawn(self.rpc_client.clone(), block_production, move || {
+            let rpc_client = rpc_client.clone();
+            let zebra_network = zebra_network.clone();
+            async move { generate_zebrad_block(&rpc_client, &zebra_network, time_source).await }
+        })
+    }
+
+    /// Zebrad has no wallet, so a block paying the miner reward to a services-managed transparent key is mined and
+    /// the coinbase output is spent to the recipient once it has matured. Each call mines
+    /// [`crate::funding::COINBASE_MATURITY`] + 2 blocks.
+    ///
+    /// Transparent coinbase outputs may only be spent to shielded outputs and the Sapling proving parameters are not
+    /// managed by this crate, so only the Orchard pool is supported. Requires the `zebrad-funding` feature, otherwise
+    /// [`crate::error::FundError::UnsupportedPool`] is returned for all pools.
+    async fn fund(
+        &self,
+        address: &str,
+        amount: Zatoshis,
+        pool: PoolType,
+    ) -> Result<TxId, FundError> {
+        if pool != PoolType::Shielded(ShieldedProtocol::Orchard) {
+            return Err(FundError::UnsupportedPool(pool));
+        }
+
+        #[cfg(feature = "zebrad-funding")]
+        {
+            self.fund_orchard(address, amount).await
+        }
+        #[cfg(not(feature = "zebrad-funding"))]
+        {
+            let _ = (address, amount);
+            Err(FundError::UnsupportedPool(pool))
+        }
+    }
 
-        let chain_height: u32 = response
+    async fn get_chain_height(&self) -> Result<BlockHeight, RpcError> {
+        let response = self
+            .rpc_call("getblockchaininfo", serde_json::json!([]))
+            .await?;
+
+        response
             .get("blocks")
             .and_then(|h| h.as_u64())
             .and_then(|h| u32::try_from(h).ok())
-            .unwrap();
-
-        BlockHeight::from_u32(chain_height)
+            .map(BlockHeight::from_u32)
+            .ok_or_else(|| RpcError::InvalidResponse(response.to_string()))
     }
 
-    async fn poll_chain_height(&self, target_height: BlockHeight) {
-        while self.get_chain_height().await < target_height {
+    async fn poll_chain_height(&self, target_height: BlockHeight) -> Result<(), RpcError> {
+        while self.get_chain_height().await? < target_height {
             std::thread::sleep(std::time::Duration::from_millis(100));
         }
+
+        Ok(())
+    }
+
+    fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+
+    fn rpc_port(&self) -> Port {
+        self.rpc_listen_port
     }
 
     fn config_dir(&self) -> &TempDir {
@@ -597,10 +1645,18 @@ impl Validator for Zebrad {
         &self.data_dir
     }
 
+    fn chain_dir(&self) -> &Path {
+        &self.chain_dir
+    }
+
     fn network(&self) -> Network {
         self.network
     }
 
+    fn activation_heights(&self) -> &network::ActivationHeights {
+        &self.activation_heights
+    }
+
     fn load_chain(
         chain_cache: PathBuf,
         validator_data_dir: PathBuf,
@@ -630,3 +1686,288 @@ impl Drop for Zebrad {
         self.stop();
     }
 }
+
+/// Submits the serialized `block` to the validator at `rpc_client`.
+async fn submit_block_data(
+    rpc_client: &RpcClient,
+    block: &[u8],
+) -> Result<(), GenerateBlocksError> {
+    let response = rpc_client
+        .call("submitblock", serde_json::json!([hex::encode(block)]))
+        .await?;
+
+    parse_submit_block_response(&response).map_err(GenerateBlocksError::SubmitBlock)
+}
+
+/// Generates and submits a single block to the Zebrad process at `rpc_client` with a timestamp from `time_source`.
+async fn generate_zebrad_block(
+    rpc_client: &RpcClient,
+    zebra_network: &zebra_chain::parameters::Network,
+    time_source: TimeSource,
+) -> Result<(), GenerateBlocksError> {
+    let block_template = get_zebrad_block_template(rpc_client).await?;
+    submit_zebrad_block_template(rpc_client, zebra_network, &block_template, time_source).await
+}
+
+/// Gets a block template for the next block from the Zebrad process at `rpc_client`.
+async fn get_zebrad_block_template(
+    rpc_client: &RpcClient,
+) -> Result<GetBlockTemplate, GenerateBlocksError> {
+    serde_json::from_value(
+        rpc_client
+            .call("getblocktemplate", serde_json::json!([]))
+            .await
+            .map_err(GenerateBlocksError::BlockTemplate)?,
+    )
+    .map_err(|e| GenerateBlocksError::InvalidBlockTemplate(e.to_string()))
+}
+
+/// Builds a block from the `block_template` with a timestamp from `time_source` and submits it to the Zebrad process
+/// at `rpc_client`.
+async fn submit_zebrad_block_template(
+    rpc_client: &RpcClient,
+    zebra_network: &zebra_chain::parameters::Network,
+    block_template: &GetBlockTemplate,
+    time_source: TimeSource,
+) -> Result<(), GenerateBlocksError> {
+    let network_upgrade = NetworkUpgrade::current(zebra_network, Height(block_template.height));
+
+    let block_data = proposal_block_from_template(block_template, time_source, network_upgrade)
+        .map_err(|e| GenerateBlocksError::InvalidBlockTemplate(e.to_string()))?
+        .zcash_serialize_to_vec()
+        .map_err(|e| GenerateBlocksError::InvalidBlockTemplate(e.to_string()))?;
+
+    submit_block_data(rpc_client, &block_data).await
+}
+
+/// Decodes a hex string RPC result into bytes.
+fn decode_hex(value: &serde_json::Value) -> Result<Vec<u8>, RpcError> {
+    value
+        .as_str()
+        .and_then(|hex_str| hex::decode(hex_str).ok())
+        .ok_or_else(|| RpcError::InvalidResponse(value.to_string()))
+}
+
+/// Generates blocks until the chain reaches `height`, generating all blocks with a single call to
+/// [`Validator::generate_blocks`]. Returns the chain height, which is unchanged if already at or above `height`.
+async fn generate_blocks_to<V: Validator>(
+    validator: &V,
+    height: BlockHeight,
+) -> Result<BlockHeight, GenerateBlocksError> {
+    let chain_height = validator.get_chain_height().await?;
+    if chain_height >= height {
+        return Ok(chain_height);
+    }
+    validator
+        .generate_blocks(u32::from(height) - u32::from(chain_height))
+        .await?;
+
+    Ok(validator.get_chain_height().await?)
+}
+
+/// Sends the `transactions` not already in the mempool to the `validator` and waits for all of them to arrive.
+/// Returns the txids of the `transactions`.
+async fn send_to_mempool<V: Validator>(
+    validator: &V,
+    transactions: &[Transaction],
+) -> Result<Vec<TxId>, GenerateBlocksError> {
+    let txids: Vec<TxId> = transactions
+        .iter()
+        .map(|transaction| transaction.txid())
+        .collect();
+
+    let mempool_txids = validator.get_raw_mempool().await?;
+    for transaction in transactions
+        .iter()
+        .filter(|transaction| !mempool_txids.contains(&transaction.txid()))
+    {
+        let mut transaction_data = Vec::new();
+        transaction
+            .write(&mut transaction_data)
+            .map_err(|e| GenerateBlocksError::TransactionSelection(e.to_string()))?;
+        validator.send_raw_transaction(&transaction_data).await?;
+    }
+    validator
+        .wait_for_mempool_txids(&txids, MEMPOOL_TIMEOUT)
+        .await?;
+
+    Ok(txids)
+}
+
+/// Selects the transactions with the given `txids` from the `block_template`, returning an error if any are missing.
+///
+/// With [`MempoolPolicy::Exclude`], the template transactions are replaced by the selected transactions in the order
+/// of `txids`. The fees of the removed transactions are deducted from the coinbase output paying to `miner_address`
+/// and the block roots are recalculated.
+fn select_transactions(
+    block_template: &mut GetBlockTemplate,
+    txids: &[TxId],
+    mempool_policy: MempoolPolicy,
+    miner_address: &str,
+) -> Result<(), GenerateBlocksError> {
+    let is_selected = |transaction: &TransactionTemplate<NonNegative>| {
+        txids
+            .iter()
+            .any(|txid| transaction.hash.0 == *txid.as_ref())
+    };
+
+    let mut selected_transactions = Vec::with_capacity(txids.len());
+    for txid in txids {
+        let transaction = block_template
+            .transactions
+            .iter()
+            .find(|transaction| transaction.hash.0 == *txid.as_ref())
+            .ok_or_else(|| {
+                GenerateBlocksError::TransactionSelection(format!(
+                    "transaction {txid} is not in the block template"
+                ))
+            })?;
+        selected_transactions.push(transaction.clone());
+    }
+    if mempool_policy == MempoolPolicy::Include {
+        return Ok(());
+    }
+
+    let excluded_fee: i64 = block_template
+        .transactions
+        .iter()
+        .filter(|transaction| !is_selected(transaction))
+        .map(|transaction| i64::from(transaction.fee))
+        .sum();
+    let selected_fee: i64 = selected_transactions
+        .iter()
+        .map(|transaction| i64::from(transaction.fee))
+        .sum();
+
+    let mut coinbase = decode_coinbase(block_template)?;
+    let (_, miner_output) = miner_output(&mut coinbase, miner_address)?;
+    miner_output.value = Amount::try_from(i64::from(miner_output.value) - excluded_fee)
+        .map_err(|e| GenerateBlocksError::InvalidBlockTemplate(e.to_string()))?;
+
+    block_template.coinbase_txn.fee = Amount::try_from(-selected_fee)
+        .map_err(|e| GenerateBlocksError::InvalidBlockTemplate(e.to_string()))?;
+    block_template.transactions = selected_transactions;
+    replace_coinbase(block_template, &coinbase)
+}
+
+/// Decodes the coinbase transaction of the `block_template`.
+fn decode_coinbase(
+    block_template: &GetBlockTemplate,
+) -> Result<zebra_chain::transaction::Transaction, GenerateBlocksError> {
+    block_template
+        .coinbase_txn
+        .data
+        .as_ref()
+        .zcash_deserialize_into()
+        .map_err(|e| GenerateBlocksError::InvalidBlockTemplate(e.to_string()))
+}
+
+/// Returns the index of the `coinbase` output paying to `miner_address` along with the output.
+fn miner_output<'a>(
+    coinbase: &'a mut zebra_chain::transaction::Transaction,
+    miner_address: &str,
+) -> Result<(usize, &'a mut transparent::Output), GenerateBlocksError> {
+    let miner_script = miner_address
+        .parse::<transparent::Address>()
+        .map_err(|e| GenerateBlocksError::InvalidBlockTemplate(e.to_string()))?
+        .create_script_from_address();
+    let outputs = match coinbase {
+        zebra_chain::transaction::Transaction::V4 { outputs, .. }
+        | zebra_chain::transaction::Transaction::V5 { outputs, .. } => outputs,
+        _ => {
+            return Err(GenerateBlocksError::InvalidBlockTemplate(
+                "unsupported coinbase version".to_string(),
+            ))
+        }
+    };
+
+    outputs
+        .iter_mut()
+        .enumerate()
+        .find(|(_, output)| output.lock_script == miner_script)
+        .ok_or_else(|| {
+            GenerateBlocksError::InvalidBlockTemplate("coinbase has no miner output".to_string())
+        })
+}
+
+/// Replaces the coinbase transaction of the `block_template` with `coinbase` and recalculates the block roots.
+fn replace_coinbase(
+    block_template: &mut GetBlockTemplate,
+    coinbase: &zebra_chain::transaction::Transaction,
+) -> Result<(), GenerateBlocksError> {
+    block_template.coinbase_txn.data = coinbase
+        .zcash_serialize_to_vec()
+        .map_err(|e| GenerateBlocksError::InvalidBlockTemplate(e.to_string()))?
+        .into();
+    block_template.coinbase_txn.hash = coinbase.hash();
+    block_template.coinbase_txn.auth_digest = coinbase
+        .auth_digest()
+        .unwrap_or(zebra_chain::transaction::AuthDigest([0xFF; 32]));
+
+    let transactions =
+        std::iter::once(&block_template.coinbase_txn).chain(&block_template.transactions);
+    let auth_data_root: AuthDataRoot = transactions
+        .clone()
+        .map(|transaction| transaction.auth_digest)
+        .collect();
+    block_template.default_roots.merkle_root =
+        transactions.map(|transaction| transaction.hash).collect();
+    block_template.default_roots.block_commitments_hash =
+        ChainHistoryBlockTxAuthCommitmentHash::from_commitments(
+            &block_template.default_roots.chain_history_root,
+            &auth_data_root,
+        );
+    block_template.default_roots.auth_data_root = auth_data_root;
+
+    Ok(())
+}
+
+/// Decodes a `submitblock` RPC result. A `null` result indicates the block was accepted.
+fn parse_submit_block_response(response: &serde_json::Value) -> Result<(), SubmitBlockError> {
+    match response.as_str() {
+        None if response.is_null() => Ok(()),
+        Some("duplicate") => Err(SubmitBlockError::Duplicate),
+        Some("duplicate-invalid") => Err(SubmitBlockError::DuplicateInvalid),
+        Some("duplicate-inconclusive") => Err(SubmitBlockError::DuplicateInconclusive),
+        Some("inconclusive") => Err(SubmitBlockError::Inconclusive),
+        Some(reason) => Err(SubmitBlockError::Rejected {
+            reason: reason.to_string(),
+        }),
+        None => Err(SubmitBlockError::Rejected {
+            reason: response.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::SubmitBlockError;
+
+    #[test]
+    fn parse_submit_block_response() {
+        assert_eq!(
+            super::parse_submit_block_response(&serde_json::Value::Null),
+            Ok(())
+        );
+        assert_eq!(
+            super::parse_submit_block_response(&serde_json::json!("duplicate")),
+            Err(SubmitBlockError::Duplicate)
+        );
+        assert_eq!(
+            super::parse_submit_block_response(&serde_json::json!("duplicate-inconclusive")),
+            Err(SubmitBlockError::DuplicateInconclusive)
+        );
+        assert_eq!(
+            super::parse_submit_block_response(&serde_json::json!("rejected")),
+            Err(SubmitBlockError::Rejected {
+                reason: "rejected".to_string()
+            })
+        );
+        assert_eq!(
+            super::parse_submit_block_response(&serde_json::json!("bad-txnmrklroot")),
+            Err(SubmitBlockError::Rejected {
+                reason: "bad-txnmrklroot".to_string()
+            })
+        );
+    }
+}
@@ -3,12 +3,20 @@
 use std::path::PathBuf;
 
 use portpicker::Port;
+use tempfile::TempDir;
 use testvectors::seeds;
 use zcash_client_backend::proto::service::compact_tx_streamer_client::CompactTxStreamerClient;
-use zingo_infra_services::network;
+use zcash_primitives::transaction::TxId;
+use zcash_protocol::{consensus::BlockHeight, value::Zatoshis, PoolType, ShieldedProtocol};
+use zingo_infra_services::{
+    error::FundError, indexer::Indexer, network, rpc::RpcClient, validator::Validator, LocalNet,
+};
 use zingo_netutils::{GetClientError, GrpcConnector, UnderlyingService};
 use zingolib::{
-    config::RegtestNetwork, lightclient::LightClient, testutils::scenarios::setup::ClientBuilder,
+    config::{load_clientconfig, ChainType, RegtestNetwork},
+    lightclient::LightClient,
+    testutils::lightclient::get_base_address,
+    wallet::WalletBase,
 };
 
 /// Builds a client for creating RPC requests to the indexer/light-node
@@ -19,25 +27,270 @@ pub fn build_client(
     GrpcConnector::new(uri).get_client()
 }
 
+/// Builds a client for creating JSON-RPC requests to the indexer listening on `json_rpc_port`
+///
+/// See [`zingo_infra_services::indexer::Zainod::json_rpc_port`]. Responses may be compared against the validator's
+/// [`zingo_infra_services::validator::Validator::rpc_client`].
+pub fn build_json_rpc_client(json_rpc_port: Port) -> RpcClient {
+    RpcClient::new(json_rpc_port)
+}
+
 // NOTE: this should be migrated to zingolib when LocalNet replaces regtest manager in zingoilb::testutils
-/// Builds faucet (miner) and recipient lightclients for local network integration testing
-pub async fn build_lightclients(
+/// Builds faucet (miner) and recipient lightclients for local network integration testing, see
+/// [`LightClientConfig::faucet`] and [`LightClientConfig::recipient`].
+///
+/// The light client network is derived from the `validator`, see [`chain_type`].
+pub async fn build_lightclients<V: Validator>(
     lightclient_dir: PathBuf,
     indexer_port: Port,
+    validator: &V,
 ) -> (LightClient, LightClient) {
-    let mut client_builder =
-        ClientBuilder::new(network::localhost_uri(indexer_port), lightclient_dir);
-    let faucet = client_builder
-        .build_faucet(true, RegtestNetwork::all_upgrades_active())
-        .await;
-    let recipient = client_builder
-        .build_client(
-            seeds::HOSPITAL_MUSEUM_SEED.to_string(),
-            1,
-            true,
-            RegtestNetwork::all_upgrades_active(),
+    let indexer_uri = network::localhost_uri(indexer_port);
+    let faucet = build_lightclient(
+        indexer_uri.clone(),
+        lightclient_dir.join("faucet"),
+        &LightClientConfig::faucet(),
+        chain_type(validator),
+    )
+    .await;
+    let recipient = build_lightclient(
+        indexer_uri,
+        lightclient_dir.join("recipient"),
+        &LightClientConfig::recipient(),
+        chain_type(validator),
+    )
+    .await;
+
+    (faucet, recipient)
+}
+
+/// Returns the light client chain type matching the network of the `validator`. For regtest, the network upgrade
+/// activation heights are also taken from the `validator`, see [`regtest_network`].
+pub fn chain_type<V: Validator>(validator: &V) -> ChainType {
+    match validator.network() {
+        network::Network::Regtest => {
+            ChainType::Regtest(regtest_network(validator.activation_heights()))
+        }
+        network::Network::Testnet => ChainType::Testnet,
+        network::Network::Mainnet => ChainType::Mainnet,
+    }
+}
+
+/// Returns the light client regtest network parameters matching the local network upgrade `activation_heights`.
+pub fn regtest_network(activation_heights: &network::ActivationHeights) -> RegtestNetwork {
+    let height = |height: BlockHeight| u64::from(u32::from(height));
+
+    RegtestNetwork::new(
+        height(activation_heights.overwinter),
+        height(activation_heights.sapling),
+        height(activation_heights.blossom),
+        height(activation_heights.heartwood),
+        height(activation_heights.canopy),
+        height(activation_heights.nu5),
+        height(activation_heights.nu6),
+    )
+}
+
+/// Builds a light client from the seed and birthday of the `config`, storing its wallet in `data_dir`.
+async fn build_lightclient(
+    indexer_uri: http::Uri,
+    data_dir: PathBuf,
+    config: &LightClientConfig,
+    chain_type: ChainType,
+) -> LightClient {
+    std::fs::create_dir_all(&data_dir).unwrap();
+    let zingo_config = load_clientconfig(indexer_uri, Some(data_dir), chain_type, true).unwrap();
+
+    LightClient::create_from_wallet_base_async(
+        WalletBase::MnemonicPhrase(config.seed.clone()),
+        &zingo_config,
+        config.birthday,
+        true,
+    )
+    .await
+    .unwrap()
+}
+
+/// Light client configuration, see [`LocalNetWithClients`].
+pub struct LightClientConfig {
+    /// Name used to access the light client
+    pub name: String,
+    /// Wallet seed phrase
+    pub seed: String,
+    /// Wallet birthday height
+    pub birthday: u64,
+    /// Pool of the light client's receiving address, see [`LocalNetWithClients::address`] and
+    /// [`LocalNetWithClients::fund`]
+    pub pool: PoolType,
+}
+
+impl LightClientConfig {
+    /// Faucet light client configuration. The faucet seed holds the default miner addresses, for example
+    /// [`testvectors::REG_O_ADDR_FROM_ABANDONART`].
+    pub fn faucet() -> Self {
+        LightClientConfig {
+            name: "faucet".to_string(),
+            seed: seeds::ABANDON_ART_SEED.to_string(),
+            birthday: 1,
+            pool: PoolType::Shielded(ShieldedProtocol::Orchard),
+        }
+    }
+
+    /// Recipient light client configuration.
+    pub fn recipient() -> Self {
+        LightClientConfig {
+            name: "recipient".to_string(),
+            seed: seeds::HOSPITAL_MUSEUM_SEED.to_string(),
+            birthday: 1,
+            pool: PoolType::Shielded(ShieldedProtocol::Orchard),
+        }
+    }
+}
+
+/// A [`LocalNet`] which owns a set of named light clients connected to its indexer.
+///
+/// The light clients are created with network parameters derived from the validator's network and activation
+/// heights, see [`chain_type`].
+pub struct LocalNetWithClients<I, V>
+where
+    I: Indexer,
+    V: Validator,
+{
+    local_net: LocalNet<I, V>,
+    lightclient_dir: TempDir,
+    lightclients: Vec<(LightClientConfig, LightClient)>,
+}
+
+impl<I, V> LocalNetWithClients<I, V>
+where
+    I: Indexer,
+    V: Validator,
+{
+    /// Creates a light client for each of the `configs`, connected to the indexer of the `local_net`.
+    ///
+    /// Panics if the indexer has no light client gRPC server, i.e. [`zingo_infra_services::indexer::Empty`].
+    pub async fn new(local_net: LocalNet<I, V>, configs: Vec<LightClientConfig>) -> Self {
+        let mut local_net_with_clients = LocalNetWithClients {
+            local_net,
+            lightclient_dir: tempfile::tempdir().unwrap(),
+            lightclients: Vec::new(),
+        };
+        for config in configs {
+            local_net_with_clients.add_lightclient(config).await;
+        }
+
+        local_net_with_clients
+    }
+
+    /// Gets local net.
+    pub fn local_net(&self) -> &LocalNet<I, V> {
+        &self.local_net
+    }
+
+    /// Gets local net as mut.
+    pub fn local_net_mut(&mut self) -> &mut LocalNet<I, V> {
+        &mut self.local_net
+    }
+
+    /// Creates a light client from the `config`, connected to the indexer, and returns it.
+    ///
+    /// Panics if a light client with the same name already exists.
+    pub async fn add_lightclient(&mut self, config: LightClientConfig) -> &LightClient {
+        if self.lightclient(&config.name).is_some() {
+            panic!("light client {} already exists!", config.name);
+        }
+
+        let indexer_uri = self
+            .local_net
+            .indexer()
+            .grpc_uri()
+            .expect("indexer should serve light client gRPC");
+        let lightclient = build_lightclient(
+            indexer_uri,
+            self.lightclient_dir.path().join(&config.name),
+            &config,
+            chain_type(self.local_net.validator()),
         )
         .await;
+        self.lightclients.push((config, lightclient));
 
-    (faucet, recipient)
+        &self
+            .lightclients
+            .last()
+            .expect("light client was just added")
+            .1
+    }
+
+    /// Gets the light client with the given `name`.
+    pub fn lightclient(&self, name: &str) -> Option<&LightClient> {
+        self.lightclients
+            .iter()
+            .find(|(config, _)| config.name == name)
+            .map(|(_, lightclient)| lightclient)
+    }
+
+    /// Gets all light clients with their names, in the order they were added.
+    pub fn lightclients(&self) -> impl Iterator<Item = (&str, &LightClient)> {
+        self.lightclients
+            .iter()
+            .map(|(config, lightclient)| (config.name.as_str(), lightclient))
+    }
+
+    /// Returns the receiving address of the light client with the given `name`, for the pool set in its config.
+    ///
+    /// Panics if there is no light client with the given `name`.
+    pub async fn address(&self, name: &str) -> String {
+        let (config, lightclient) = self
+            .lightclients
+            .iter()
+            .find(|(config, _)| config.name == name)
+            .unwrap_or_else(|| panic!("light client {name} not found!"));
+
+        get_base_address(lightclient, config.pool).await
+    }
+
+    /// Funds the light client with the given `name` by sending `amount` from the validator's mining rewards to the
+    /// receiver for the pool set in its config. Waits for the indexer to serve the block confirming the funding
+    /// transaction and syncs all light clients. Returns the txid of the funding transaction.
+    ///
+    /// See [`Validator::fund`] for the pools each validator can fund.
+    ///
+    /// Panics if there is no light client with the given `name`.
+    pub async fn fund(&self, name: &str, amount: Zatoshis) -> Result<TxId, FundError> {
+        let address = self.address(name).await;
+        let (config, _) = self
+            .lightclients
+            .iter()
+            .find(|(config, _)| config.name == name)
+            .expect("light client should exist after getting its address");
+
+        let txid = self
+            .local_net
+            .validator()
+            .fund(&address, amount, config.pool)
+            .await?;
+        // the funding transaction is already mined so no further blocks are generated
+        self.local_net.generate_blocks_and_sync(0).await?;
+        self.sync_all().await;
+
+        Ok(txid)
+    }
+
+    /// Syncs all light clients.
+    pub async fn sync_all(&self) {
+        for (_, lightclient) in &self.lightclients {
+            lightclient.do_sync(false).await.unwrap();
+        }
+    }
+
+    /// Generates `n` blocks, waits for the indexer to sync and then syncs all light clients.
+    /// Returns the new chain height.
+    ///
+    /// See [`LocalNet::generate_blocks_and_sync`].
+    pub async fn generate_blocks_and_sync(&self, n: u32) -> BlockHeight {
+        let chain_height = self.local_net.generate_blocks_and_sync(n).await.unwrap();
+        self.sync_all().await;
+
+        chain_height
+    }
 }
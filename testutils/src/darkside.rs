@@ -0,0 +1,310 @@
+//! Module for the client of Lightwalletd's darkside mode.
+//!
+//! In darkside mode Lightwalletd serves a chain staged by the test over the `DarksideStreamer` gRPC service instead
+//! of syncing from a validator. Launch Lightwalletd with `darkside` set in
+//! [`zingo_infra_services::indexer::LightwalletdConfig`].
+//!
+//! Example usage:
+//! ```ignore (incomplete)
+//! let mut darkside = DarksideStreamerClient::connect(lightwalletd.grpc_uri().unwrap()).await?;
+//! darkside.reset(DarksideMetaState::regtest(1)).await?;
+//! darkside.stage_blocks_create(1, 0, 10).await?;
+//! darkside.apply_staged(10).await?;
+//! ```
+
+use zcash_client_backend::proto::service::{
+    BlockId, Empty, GetAddressUtxosReply, RawTransaction, TreeState,
+};
+
+/// Consensus branch ID of NU5, as expected by [`DarksideMetaState::branch_id`].
+pub const NU5_BRANCH_ID: &str = "c2d6d0b4";
+
+const RESET: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/Reset";
+const STAGE_BLOCKS_STREAM: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/StageBlocksStream";
+const STAGE_BLOCKS: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/StageBlocks";
+const STAGE_BLOCKS_CREATE: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/StageBlocksCreate";
+const STAGE_TRANSACTIONS_STREAM: &str =
+    "/cash.z.wallet.sdk.rpc.DarksideStreamer/StageTransactionsStream";
+const STAGE_TRANSACTIONS: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/StageTransactions";
+const APPLY_STAGED: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/ApplyStaged";
+const GET_INCOMING_TRANSACTIONS: &str =
+    "/cash.z.wallet.sdk.rpc.DarksideStreamer/GetIncomingTransactions";
+const CLEAR_INCOMING_TRANSACTIONS: &str =
+    "/cash.z.wallet.sdk.rpc.DarksideStreamer/ClearIncomingTransactions";
+const ADD_ADDRESS_UTXO: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/AddAddressUtxo";
+const CLEAR_ADDRESS_UTXO: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/ClearAddressUtxo";
+const ADD_TREE_STATE: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/AddTreeState";
+const REMOVE_TREE_STATE: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/RemoveTreeState";
+const CLEAR_ALL_TREE_STATES: &str = "/cash.z.wallet.sdk.rpc.DarksideStreamer/ClearAllTreeStates";
+
+/// Parameters of the darkside chain, set by [`DarksideStreamerClient::reset`].
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DarksideMetaState {
+    /// Sapling activation height
+    #[prost(int32, tag = "1")]
+    pub sapling_activation: i32,
+    /// Consensus branch ID, hex encoded
+    #[prost(string, tag = "2")]
+    pub branch_id: String,
+    /// Chain name reported by `GetLightdInfo`, i.e. "main", "test" or "regtest"
+    #[prost(string, tag = "3")]
+    pub chain_name: String,
+    /// Size of the Sapling note commitment tree before the first staged block
+    #[prost(uint32, tag = "4")]
+    pub start_sapling_commitment_tree_size: u32,
+    /// Size of the Orchard note commitment tree before the first staged block
+    #[prost(uint32, tag = "5")]
+    pub start_orchard_commitment_tree_size: u32,
+}
+
+impl DarksideMetaState {
+    /// Returns the parameters of a regtest chain with NU5 active, starting from `sapling_activation` with empty note
+    /// commitment trees.
+    pub fn regtest(sapling_activation: i32) -> Self {
+        DarksideMetaState {
+            sapling_activation,
+            branch_id: NU5_BRANCH_ID.to_string(),
+            chain_name: "regtest".to_string(),
+            start_sapling_commitment_tree_size: 0,
+            start_orchard_commitment_tree_size: 0,
+        }
+    }
+}
+
+/// A block in hex encoded consensus serialization.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DarksideBlock {
+    /// Hex encoded block
+    #[prost(string, tag = "1")]
+    pub block: String,
+}
+
+/// URL of a file of hex encoded blocks, one per line.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DarksideBlocksUrl {
+    /// File URL
+    #[prost(string, tag = "1")]
+    pub url: String,
+}
+
+/// URL of a file of hex encoded transactions, one per line, to be staged at `height`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DarksideTransactionsUrl {
+    /// Height of the block the transactions are added to
+    #[prost(int32, tag = "1")]
+    pub height: i32,
+    /// File URL
+    #[prost(string, tag = "2")]
+    pub url: String,
+}
+
+/// A darkside chain height.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DarksideHeight {
+    /// Block height
+    #[prost(int32, tag = "1")]
+    pub height: i32,
+}
+
+/// Parameters for creating empty blocks.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DarksideEmptyBlocks {
+    /// Height of the first block
+    #[prost(int32, tag = "1")]
+    pub height: i32,
+    /// Nonce, changing the block hashes of otherwise identical blocks
+    #[prost(int32, tag = "2")]
+    pub nonce: i32,
+    /// Number of blocks
+    #[prost(int32, tag = "3")]
+    pub count: i32,
+}
+
+/// Client for the `DarksideStreamer` gRPC service of Lightwalletd launched in darkside mode.
+///
+/// Blocks and transactions are staged and only served once [`Self::apply_staged`] is called, allowing reorgs to be
+/// simulated by staging a competing chain.
+#[derive(Clone)]
+pub struct DarksideStreamerClient {
+    /// gRPC client
+    grpc: tonic::client::Grpc<tonic::transport::Channel>,
+}
+
+impl DarksideStreamerClient {
+    /// Connects to the darkside Lightwalletd listening at `uri`.
+    pub async fn connect(uri: http::Uri) -> Result<Self, tonic::transport::Error> {
+        let channel = tonic::transport::Channel::builder(uri).connect().await?;
+
+        Ok(DarksideStreamerClient {
+            grpc: tonic::client::Grpc::new(channel),
+        })
+    }
+
+    /// Resets the darkside chain with the given parameters, clearing all staged and applied state.
+    pub async fn reset(&mut self, meta_state: DarksideMetaState) -> Result<(), tonic::Status> {
+        self.unary(RESET, meta_state).await
+    }
+
+    /// Stages the hex encoded `blocks`.
+    pub async fn stage_blocks_stream(&mut self, blocks: Vec<String>) -> Result<(), tonic::Status> {
+        let blocks = blocks
+            .into_iter()
+            .map(|block| DarksideBlock { block })
+            .collect();
+        self.client_streaming(STAGE_BLOCKS_STREAM, blocks).await
+    }
+
+    /// Stages the hex encoded blocks from the file at `url`, one per line.
+    pub async fn stage_blocks(&mut self, url: String) -> Result<(), tonic::Status> {
+        self.unary(STAGE_BLOCKS, DarksideBlocksUrl { url }).await
+    }
+
+    /// Stages `count` empty blocks starting at `height`. Blocks with a different `nonce` have different hashes.
+    pub async fn stage_blocks_create(
+        &mut self,
+        height: i32,
+        nonce: i32,
+        count: i32,
+    ) -> Result<(), tonic::Status> {
+        self.unary(
+            STAGE_BLOCKS_CREATE,
+            DarksideEmptyBlocks {
+                height,
+                nonce,
+                count,
+            },
+        )
+        .await
+    }
+
+    /// Stages the serialized `transactions` to be added to the block at `height`.
+    pub async fn stage_transactions_stream(
+        &mut self,
+        height: u64,
+        transactions: Vec<Vec<u8>>,
+    ) -> Result<(), tonic::Status> {
+        let transactions = transactions
+            .into_iter()
+            .map(|data| RawTransaction { data, height })
+            .collect();
+        self.client_streaming(STAGE_TRANSACTIONS_STREAM, transactions)
+            .await
+    }
+
+    /// Stages the hex encoded transactions from the file at `url`, one per line, to be added to the block at
+    /// `height`.
+    pub async fn stage_transactions(
+        &mut self,
+        height: i32,
+        url: String,
+    ) -> Result<(), tonic::Status> {
+        self.unary(STAGE_TRANSACTIONS, DarksideTransactionsUrl { height, url })
+            .await
+    }
+
+    /// Applies the staged blocks and transactions, serving the chain up to `height`.
+    pub async fn apply_staged(&mut self, height: i32) -> Result<(), tonic::Status> {
+        self.unary(APPLY_STAGED, DarksideHeight { height }).await
+    }
+
+    /// Returns the transactions sent to Lightwalletd with `SendTransaction`.
+    pub async fn get_incoming_transactions(
+        &mut self,
+    ) -> Result<Vec<RawTransaction>, tonic::Status> {
+        self.grpc.ready().await.map_err(not_ready)?;
+        let mut response = self
+            .grpc
+            .server_streaming(
+                tonic::Request::new(Empty {}),
+                http::uri::PathAndQuery::from_static(GET_INCOMING_TRANSACTIONS),
+                tonic::codec::ProstCodec::default(),
+            )
+            .await?
+            .into_inner();
+
+        let mut transactions = Vec::new();
+        while let Some(transaction) = response.message().await? {
+            transactions.push(transaction);
+        }
+
+        Ok(transactions)
+    }
+
+    /// Clears the transactions sent to Lightwalletd with `SendTransaction`.
+    pub async fn clear_incoming_transactions(&mut self) -> Result<(), tonic::Status> {
+        self.unary(CLEAR_INCOMING_TRANSACTIONS, Empty {}).await
+    }
+
+    /// Adds a UTXO served by `GetAddressUtxos` and `GetAddressUtxosStream`.
+    pub async fn add_address_utxo(
+        &mut self,
+        utxo: GetAddressUtxosReply,
+    ) -> Result<(), tonic::Status> {
+        self.unary(ADD_ADDRESS_UTXO, utxo).await
+    }
+
+    /// Clears all UTXOs added with [`Self::add_address_utxo`].
+    pub async fn clear_address_utxo(&mut self) -> Result<(), tonic::Status> {
+        self.unary(CLEAR_ADDRESS_UTXO, Empty {}).await
+    }
+
+    /// Adds a tree state served by `GetTreeState`.
+    pub async fn add_tree_state(&mut self, tree_state: TreeState) -> Result<(), tonic::Status> {
+        self.unary(ADD_TREE_STATE, tree_state).await
+    }
+
+    /// Removes the tree state of the block `block_id`.
+    pub async fn remove_tree_state(&mut self, block_id: BlockId) -> Result<(), tonic::Status> {
+        self.unary(REMOVE_TREE_STATE, block_id).await
+    }
+
+    /// Clears all tree states added with [`Self::add_tree_state`].
+    pub async fn clear_all_tree_states(&mut self) -> Result<(), tonic::Status> {
+        self.unary(CLEAR_ALL_TREE_STATES, Empty {}).await
+    }
+
+    /// Calls the unary RPC at `path` with the `request` message, discarding the empty response.
+    async fn unary<M>(&mut self, path: &'static str, request: M) -> Result<(), tonic::Status>
+    where
+        M: prost::Message + Send + Sync + 'static,
+    {
+        self.grpc.ready().await.map_err(not_ready)?;
+        let _: tonic::Response<Empty> = self
+            .grpc
+            .unary(
+                tonic::Request::new(request),
+                http::uri::PathAndQuery::from_static(path),
+                tonic::codec::ProstCodec::default(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Calls the client streaming RPC at `path` with the `requests` messages, discarding the empty response.
+    async fn client_streaming<M>(
+        &mut self,
+        path: &'static str,
+        requests: Vec<M>,
+    ) -> Result<(), tonic::Status>
+    where
+        M: prost::Message + Send + Sync + 'static,
+    {
+        self.grpc.ready().await.map_err(not_ready)?;
+        let _: tonic::Response<Empty> = self
+            .grpc
+            .client_streaming(
+                tonic::Request::new(tokio_stream::iter(requests)),
+                http::uri::PathAndQuery::from_static(path),
+                tonic::codec::ProstCodec::default(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Converts a connection error while waiting for the channel to be ready into a gRPC status.
+fn not_ready(error: tonic::transport::Error) -> tonic::Status {
+    tonic::Status::unavailable(format!("darkside service was not ready: {error}"))
+}
@@ -39,35 +39,24 @@ use zingolib::{
 use crate::client;
 
 use zingo_infra_services::{
-    config,
-    indexer::{Indexer as _, Lightwalletd, LightwalletdConfig, Zainod, ZainodConfig},
+    indexer::{AnyIndexerConfig, Indexer as _, Lightwalletd, LightwalletdConfig, Zainod},
     network::{self, Network},
     utils,
-    validator::{Validator as _, Zcashd, ZcashdConfig, Zebrad, ZebradConfig, ZEBRAD_DEFAULT_MINER},
-    LocalNet,
+    validator::{Validator, Zcashd, Zebrad},
+    LocalNet, MEMPOOL_TIMEOUT,
 };
+
 /// Generates zebrad chain cache for client RPC test fixtures requiring a large chain
 pub async fn generate_zebrad_large_chain_cache(
     zebrad_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let mut local_net = LocalNet::<Lightwalletd, Zebrad>::launch(
-        LightwalletdConfig {
-            lightwalletd_bin,
-            listen_port: None,
-            zcashd_conf: PathBuf::new(),
-        },
-        ZebradConfig {
-            zebrad_bin,
-            network_listen_port: None,
-            rpc_listen_port: None,
-            activation_heights: network::ActivationHeights::default(),
-            miner_address: ZEBRAD_DEFAULT_MINER,
-            chain_cache: None,
-            network: Network::Regtest,
-        },
-    )
-    .await;
+    let mut local_net = LocalNet::<Lightwalletd, Zebrad>::builder()
+        .validator_config(|config| config.zebrad_bin = zebrad_bin)
+        .indexer_config(|config| config.lightwalletd_bin = lightwalletd_bin)
+        .launch()
+        .await
+        .unwrap();
 
     local_net.validator().generate_blocks(150).await.unwrap();
 
@@ -86,22 +75,16 @@ pub async fn generate_zcashd_chain_cache(
     zcash_cli_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let mut local_net = LocalNet::<Lightwalletd, Zcashd>::launch(
-        LightwalletdConfig {
-            lightwalletd_bin,
-            listen_port: None,
-            zcashd_conf: PathBuf::new(),
-        },
-        ZcashdConfig {
-            zcashd_bin,
-            zcash_cli_bin,
-            rpc_listen_port: None,
-            activation_heights: network::ActivationHeights::default(),
-            miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-            chain_cache: None,
-        },
-    )
-    .await;
+    let mut local_net = LocalNet::<Lightwalletd, Zcashd>::builder()
+        .validator_config(|config| {
+            config.zcashd_bin = zcashd_bin;
+            config.zcash_cli_bin = zcash_cli_bin;
+        })
+        .indexer_config(|config| config.lightwalletd_bin = lightwalletd_bin)
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .launch()
+        .await
+        .unwrap();
 
     local_net.validator().generate_blocks(2).await.unwrap();
 
@@ -109,6 +92,7 @@ pub async fn generate_zcashd_chain_cache(
     let (faucet, recipient) = client::build_lightclients(
         lightclient_dir.path().to_path_buf(),
         local_net.indexer().port(),
+        local_net.validator(),
     )
     .await;
 
@@ -229,34 +213,13 @@ pub async fn get_lightd_info(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(proto::service::Empty {});
     let zainod_response = zainod_client
         .get_lightd_info(request)
@@ -264,9 +227,10 @@ pub async fn get_lightd_info(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(proto::service::Empty {});
     let lwd_response = lwd_client
         .get_lightd_info(request)
@@ -337,36 +301,15 @@ pub async fn get_latest_block(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(proto::service::ChainSpec {});
     let zainod_response = zainod_client
         .get_latest_block(request)
@@ -374,9 +317,10 @@ pub async fn get_latest_block(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(proto::service::ChainSpec {});
     let lwd_response = lwd_client
         .get_latest_block(request)
@@ -404,30 +348,8 @@ pub async fn get_block(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -436,15 +358,17 @@ pub async fn get_block(
         hash: vec![],
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let zainod_response = zainod_client.get_block(request).await.unwrap().into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let lwd_response = lwd_client.get_block(request).await.unwrap().into_inner();
 
@@ -473,30 +397,8 @@ pub async fn get_block_out_of_bounds(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -505,15 +407,17 @@ pub async fn get_block_out_of_bounds(
         hash: vec![],
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let zainod_err_status = zainod_client.get_block(request).await.unwrap_err();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let lwd_err_status = lwd_client.get_block(request).await.unwrap_err();
 
@@ -538,30 +442,8 @@ pub async fn get_block_nullifiers(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -570,9 +452,10 @@ pub async fn get_block_nullifiers(
         hash: vec![],
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let zainod_response = zainod_client
         .get_block_nullifiers(request)
@@ -580,9 +463,10 @@ pub async fn get_block_nullifiers(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let lwd_response = lwd_client
         .get_block_nullifiers(request)
@@ -615,30 +499,8 @@ pub async fn get_block_range_nullifiers(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -653,9 +515,10 @@ pub async fn get_block_range_nullifiers(
         }),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut zainod_response = zainod_client
         .get_block_range_nullifiers(request)
@@ -667,9 +530,10 @@ pub async fn get_block_range_nullifiers(
         zainod_blocks.push(compact_block);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut lwd_response = lwd_client
         .get_block_range_nullifiers(request)
@@ -706,30 +570,8 @@ pub async fn get_block_range_nullifiers_reverse(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -744,9 +586,10 @@ pub async fn get_block_range_nullifiers_reverse(
         }),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut zainod_response = zainod_client
         .get_block_range_nullifiers(request)
@@ -758,9 +601,10 @@ pub async fn get_block_range_nullifiers_reverse(
         zainod_blocks.push(compact_block);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut lwd_response = lwd_client
         .get_block_range_nullifiers(request)
@@ -797,30 +641,8 @@ pub async fn get_block_range_lower(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -835,9 +657,10 @@ pub async fn get_block_range_lower(
         }),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut zainod_response = zainod_client
         .get_block_range(request)
@@ -849,9 +672,10 @@ pub async fn get_block_range_lower(
         zainod_blocks.push(compact_block);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut lwd_response = lwd_client
         .get_block_range(request)
@@ -888,30 +712,8 @@ pub async fn get_block_range_upper(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -926,9 +728,10 @@ pub async fn get_block_range_upper(
         }),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut zainod_response = zainod_client
         .get_block_range(request)
@@ -940,9 +743,10 @@ pub async fn get_block_range_upper(
         zainod_blocks.push(compact_block);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut lwd_response = lwd_client
         .get_block_range(request)
@@ -978,30 +782,8 @@ pub async fn get_block_range_reverse(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1016,9 +798,10 @@ pub async fn get_block_range_reverse(
         }),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut zainod_response = zainod_client
         .get_block_range(request)
@@ -1030,9 +813,10 @@ pub async fn get_block_range_reverse(
         zainod_blocks.push(compact_block);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut lwd_response = lwd_client
         .get_block_range(request)
@@ -1069,30 +853,8 @@ pub async fn get_block_range_out_of_bounds(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1107,9 +869,10 @@ pub async fn get_block_range_out_of_bounds(
         }),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut zainod_response = zainod_client
         .get_block_range(request)
@@ -1128,9 +891,10 @@ pub async fn get_block_range_out_of_bounds(
         zainod_blocks.push(compact_block);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_range.clone());
     let mut lwd_response = lwd_client
         .get_block_range(request)
@@ -1185,37 +949,19 @@ pub async fn get_transaction(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     // TODO: get txid from chain cache
     let lightclient_dir = tempfile::tempdir().unwrap();
-    let (faucet, recipient) =
-        client::build_lightclients(lightclient_dir.path().to_path_buf(), lightwalletd.port()).await;
+    let (faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        lightwalletd(&local_net).port(),
+        local_net.validator(),
+    )
+    .await;
     faucet.do_sync(false).await.unwrap();
     let txids = from_inputs::quick_send(
         &faucet,
@@ -1227,7 +973,7 @@ pub async fn get_transaction(
     )
     .await
     .unwrap();
-    zcashd.generate_blocks(1).await.unwrap();
+    local_net.validator().generate_blocks(1).await.unwrap();
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1237,9 +983,10 @@ pub async fn get_transaction(
         hash: txids.first().as_ref().to_vec(),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(tx_filter.clone());
     let zainod_response = zainod_client
         .get_transaction(request)
@@ -1247,9 +994,10 @@ pub async fn get_transaction(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(tx_filter.clone());
     let lwd_response = lwd_client
         .get_transaction(request)
@@ -1279,24 +1027,17 @@ pub async fn send_transaction(
     zainod_bin: Option<PathBuf>,
     _lightwalletd_bin: Option<PathBuf>,
 ) {
-    let local_net = LocalNet::<Zainod, Zcashd>::launch(
-        ZainodConfig {
-            zainod_bin: zainod_bin.clone(),
-            listen_port: None,
-            validator_port: 0,
-            chain_cache: None,
-            network: network::Network::Regtest,
-        },
-        ZcashdConfig {
-            zcashd_bin: zcashd_bin.clone(),
-            zcash_cli_bin: zcash_cli_bin.clone(),
-            rpc_listen_port: None,
-            activation_heights: network::ActivationHeights::default(),
-            miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-            chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-        },
-    )
-    .await;
+    let local_net = LocalNet::<Zainod, Zcashd>::builder()
+        .validator_config(|config| {
+            config.zcashd_bin = zcashd_bin.clone();
+            config.zcash_cli_bin = zcash_cli_bin.clone();
+        })
+        .indexer_config(|config| config.zainod_bin = zainod_bin.clone())
+        .chain_cache(utils::chain_cache_dir().join("client_rpc_tests"))
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .launch()
+        .await
+        .unwrap();
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1304,6 +1045,7 @@ pub async fn send_transaction(
     let (faucet, recipient) = client::build_lightclients(
         lightclient_dir.path().to_path_buf(),
         local_net.indexer().port(),
+        local_net.validator(),
     )
     .await;
     faucet.do_sync(false).await.unwrap();
@@ -1342,24 +1084,17 @@ pub async fn send_transaction(
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
-    let local_net = LocalNet::<Zainod, Zcashd>::launch(
-        ZainodConfig {
-            zainod_bin,
-            listen_port: None,
-            validator_port: 0,
-            chain_cache: None,
-            network: network::Network::Regtest,
-        },
-        ZcashdConfig {
-            zcashd_bin,
-            zcash_cli_bin,
-            rpc_listen_port: None,
-            activation_heights: network::ActivationHeights::default(),
-            miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-            chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-        },
-    )
-    .await;
+    let local_net = LocalNet::<Zainod, Zcashd>::builder()
+        .validator_config(|config| {
+            config.zcashd_bin = zcashd_bin;
+            config.zcash_cli_bin = zcash_cli_bin;
+        })
+        .indexer_config(|config| config.zainod_bin = zainod_bin)
+        .chain_cache(utils::chain_cache_dir().join("client_rpc_tests"))
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .launch()
+        .await
+        .unwrap();
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1367,6 +1102,7 @@ pub async fn send_transaction(
     let (faucet, recipient) = client::build_lightclients(
         lightclient_dir.path().to_path_buf(),
         local_net.indexer().port(),
+        local_net.validator(),
     )
     .await;
     faucet.do_sync(false).await.unwrap();
@@ -1439,30 +1175,8 @@ pub async fn get_taddress_txids_all(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1484,9 +1198,10 @@ pub async fn get_taddress_txids_all(
         range: Some(block_range),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(taddr_block_filter.clone());
     let mut zainod_response = zainod_client
         .get_taddress_txids(request)
@@ -1509,9 +1224,10 @@ pub async fn get_taddress_txids_all(
         .collect::<Vec<_>>();
     zainod_txs.sort_by_key(|a| a.txid());
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(taddr_block_filter.clone());
     let mut lwd_response = lwd_client
         .get_taddress_txids(request)
@@ -1555,30 +1271,8 @@ pub async fn get_taddress_txids_lower(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1600,9 +1294,10 @@ pub async fn get_taddress_txids_lower(
         range: Some(block_range),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(taddr_block_filter.clone());
     let mut zainod_response = zainod_client
         .get_taddress_txids(request)
@@ -1625,9 +1320,10 @@ pub async fn get_taddress_txids_lower(
         .collect::<Vec<_>>();
     zainod_txs.sort_by_key(|a| a.txid());
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(taddr_block_filter.clone());
     let mut lwd_response = lwd_client
         .get_taddress_txids(request)
@@ -1671,30 +1367,8 @@ pub async fn get_taddress_txids_upper(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1716,9 +1390,10 @@ pub async fn get_taddress_txids_upper(
         range: Some(block_range),
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(taddr_block_filter.clone());
     let mut zainod_response = zainod_client
         .get_taddress_txids(request)
@@ -1741,9 +1416,10 @@ pub async fn get_taddress_txids_upper(
         .collect::<Vec<_>>();
     zainod_txs.sort_by_key(|a| a.txid());
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(taddr_block_filter.clone());
     let mut lwd_response = lwd_client
         .get_taddress_txids(request)
@@ -1787,30 +1463,8 @@ pub async fn get_taddress_balance(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1821,9 +1475,10 @@ pub async fn get_taddress_balance(
         ],
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_list.clone());
     let zainod_response = zainod_client
         .get_taddress_balance(request)
@@ -1831,9 +1486,10 @@ pub async fn get_taddress_balance(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_list.clone());
     let lwd_response = lwd_client
         .get_taddress_balance(request)
@@ -1862,30 +1518,8 @@ pub async fn get_taddress_balance_stream(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -1898,9 +1532,10 @@ pub async fn get_taddress_balance_stream(
         },
     ];
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(tokio_stream::iter(address_list.clone()));
     let zainod_response = zainod_client
         .get_taddress_balance_stream(request)
@@ -1908,9 +1543,10 @@ pub async fn get_taddress_balance_stream(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(tokio_stream::iter(address_list.clone()));
     let lwd_response = lwd_client
         .get_taddress_balance_stream(request)
@@ -1939,36 +1575,18 @@ pub async fn get_mempool_tx(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     let lightclient_dir = tempfile::tempdir().unwrap();
-    let (faucet, recipient) =
-        client::build_lightclients(lightclient_dir.path().to_path_buf(), lightwalletd.port()).await;
+    let (faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        lightwalletd(&local_net).port(),
+        local_net.validator(),
+    )
+    .await;
 
     faucet.do_sync(false).await.unwrap();
     let txids_1 = from_inputs::quick_send(
@@ -2015,7 +1633,27 @@ pub async fn get_mempool_tx(
     )
     .await
     .unwrap();
-    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    let txids = [
+        *txids_1.first(),
+        *txids_2.first(),
+        *txids_3.first(),
+        *txids_4.first(),
+    ];
+    zcashd
+        .wait_for_mempool_txids(&txids, MEMPOOL_TIMEOUT)
+        .await
+        .unwrap();
+    for txid in txids {
+        zainod
+            .wait_for_mempool_tx(txid, MEMPOOL_TIMEOUT)
+            .await
+            .unwrap();
+        lightwalletd
+            .wait_for_mempool_tx(txid, MEMPOOL_TIMEOUT)
+            .await
+            .unwrap();
+    }
 
     let full_txid_2 = txids_2.first().as_ref().to_vec();
     // the excluded list only accepts truncated txids when they are truncated at the start, not end.
@@ -2026,9 +1664,10 @@ pub async fn get_mempool_tx(
         txid: vec![full_txid_2, truncated_txid_4],
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(exclude_list.clone());
     let mut zainod_response = zainod_client
         .get_mempool_tx(request)
@@ -2041,9 +1680,10 @@ pub async fn get_mempool_tx(
     }
     zainod_txs.sort_by(|a, b| a.hash.cmp(&b.hash));
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(exclude_list.clone());
     let mut lwd_response = lwd_client
         .get_mempool_tx(request)
@@ -2087,39 +1727,21 @@ pub async fn get_mempool_stream_zingolib_mempool_monitor(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     let lightclient_dir = tempfile::tempdir().unwrap();
-    let (faucet, recipient) =
-        client::build_lightclients(lightclient_dir.path().to_path_buf(), lightwalletd.port()).await;
+    let (faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        lightwalletd(&local_net).port(),
+        local_net.validator(),
+    )
+    .await;
 
     faucet.do_sync(false).await.unwrap();
-    let _txids_1 = from_inputs::quick_send(
+    let txids_1 = from_inputs::quick_send(
         &faucet,
         vec![(
             &get_base_address(&recipient, PoolType::Shielded(ShieldedProtocol::Orchard)).await,
@@ -2129,7 +1751,7 @@ pub async fn get_mempool_stream_zingolib_mempool_monitor(
     )
     .await
     .unwrap();
-    let _txids_2 = from_inputs::quick_send(
+    let txids_2 = from_inputs::quick_send(
         &faucet,
         vec![(
             &get_base_address(&recipient, PoolType::Shielded(ShieldedProtocol::Sapling)).await,
@@ -2143,7 +1765,7 @@ pub async fn get_mempool_stream_zingolib_mempool_monitor(
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     recipient.do_sync(false).await.unwrap();
-    let _txids_3 = from_inputs::quick_send(
+    let txids_3 = from_inputs::quick_send(
         &recipient,
         vec![(
             &get_base_address(&faucet, PoolType::Shielded(ShieldedProtocol::Orchard)).await,
@@ -2153,7 +1775,7 @@ pub async fn get_mempool_stream_zingolib_mempool_monitor(
     )
     .await
     .unwrap();
-    let _txids_4 = from_inputs::quick_send(
+    let txids_4 = from_inputs::quick_send(
         &recipient,
         vec![(
             &get_base_address(&faucet, PoolType::Shielded(ShieldedProtocol::Sapling)).await,
@@ -2163,7 +1785,27 @@ pub async fn get_mempool_stream_zingolib_mempool_monitor(
     )
     .await
     .unwrap();
-    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    let txids = [
+        *txids_1.first(),
+        *txids_2.first(),
+        *txids_3.first(),
+        *txids_4.first(),
+    ];
+    zcashd
+        .wait_for_mempool_txids(&txids, MEMPOOL_TIMEOUT)
+        .await
+        .unwrap();
+    for txid in txids {
+        zainod
+            .wait_for_mempool_tx(txid, MEMPOOL_TIMEOUT)
+            .await
+            .unwrap();
+        lightwalletd
+            .wait_for_mempool_tx(txid, MEMPOOL_TIMEOUT)
+            .await
+            .unwrap();
+    }
 
     drop(recipient);
     drop(faucet);
@@ -2171,8 +1813,12 @@ pub async fn get_mempool_stream_zingolib_mempool_monitor(
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     let lightclient_dir = tempfile::tempdir().unwrap();
-    let (_faucet, recipient) =
-        client::build_lightclients(lightclient_dir.path().to_path_buf(), zainod.port()).await;
+    let (_faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        local_net.indexer().port(),
+        local_net.validator(),
+    )
+    .await;
 
     let recipient = Arc::new(recipient);
 
@@ -2195,8 +1841,12 @@ pub async fn get_mempool_stream_zingolib_mempool_monitor(
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     let lightclient_dir = tempfile::tempdir().unwrap();
-    let (_faucet, recipient) =
-        client::build_lightclients(lightclient_dir.path().to_path_buf(), lightwalletd.port()).await;
+    let (_faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        lightwalletd(&local_net).port(),
+        local_net.validator(),
+    )
+    .await;
 
     let recipient = Arc::new(recipient);
 
@@ -2281,37 +1931,15 @@ pub async fn get_mempool_stream(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     // start mempool tasks
     let (zainod_sender, mut zainod_receiver) =
         unbounded_channel::<proto::service::RawTransaction>();
-    let zainod_port = zainod.port();
+    let zainod_port = local_net.indexer().port();
     let _zainod_handle = tokio::spawn(async move {
         let mut zainod_client = client::build_client(network::localhost_uri(zainod_port))
             .await
@@ -2331,7 +1959,7 @@ pub async fn get_mempool_stream(
     });
 
     let (lwd_sender, mut lwd_receiver) = unbounded_channel::<proto::service::RawTransaction>();
-    let lwd_port = lightwalletd.port();
+    let lwd_port = lightwalletd(&local_net).port();
     let _lwd_handle = tokio::spawn(async move {
         let mut lwd_client = client::build_client(network::localhost_uri(lwd_port))
             .await
@@ -2354,8 +1982,12 @@ pub async fn get_mempool_stream(
 
     // send txs to mempool
     let lightclient_dir = tempfile::tempdir().unwrap();
-    let (faucet, recipient) =
-        client::build_lightclients(lightclient_dir.path().to_path_buf(), lightwalletd.port()).await;
+    let (faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        lightwalletd(&local_net).port(),
+        local_net.validator(),
+    )
+    .await;
 
     faucet.do_sync(false).await.unwrap();
     let txids_1 = from_inputs::quick_send(
@@ -2532,8 +2164,12 @@ pub async fn get_mempool_stream(
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     let lightclient_dir = tempfile::tempdir().unwrap();
-    let (_faucet, recipient) =
-        client::build_lightclients(lightclient_dir.path().to_path_buf(), zainod.port()).await;
+    let (_faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        local_net.indexer().port(),
+        local_net.validator(),
+    )
+    .await;
 
     let recipient = Arc::new(recipient);
     LightClient::start_mempool_monitor(recipient.clone()).unwrap();
@@ -2552,8 +2188,12 @@ pub async fn get_mempool_stream(
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
     let lightclient_dir = tempfile::tempdir().unwrap();
-    let (_faucet, recipient) =
-        client::build_lightclients(lightclient_dir.path().to_path_buf(), lightwalletd.port()).await;
+    let (_faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        lightwalletd(&local_net).port(),
+        local_net.validator(),
+    )
+    .await;
 
     let recipient = Arc::new(recipient);
     LightClient::start_mempool_monitor(recipient.clone()).unwrap();
@@ -2634,30 +2274,8 @@ pub async fn get_tree_state_by_height(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -2666,9 +2284,10 @@ pub async fn get_tree_state_by_height(
         hash: vec![],
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let zainod_response = zainod_client
         .get_tree_state(request)
@@ -2676,9 +2295,10 @@ pub async fn get_tree_state_by_height(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let lwd_response = lwd_client
         .get_tree_state(request)
@@ -2706,30 +2326,8 @@ pub async fn get_tree_state_by_hash(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -2738,9 +2336,10 @@ pub async fn get_tree_state_by_hash(
         hash: vec![],
     };
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let block = lwd_client.get_block(request).await.unwrap().into_inner();
     let mut block_hash = block.hash().0.to_vec();
@@ -2751,9 +2350,10 @@ pub async fn get_tree_state_by_hash(
         hash: block_hash,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let zainod_response = zainod_client
         .get_tree_state(request)
@@ -2788,30 +2388,8 @@ pub async fn get_tree_state_out_of_bounds(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -2820,15 +2398,17 @@ pub async fn get_tree_state_out_of_bounds(
         hash: vec![],
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let zainod_err_status = zainod_client.get_tree_state(request).await.unwrap_err();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(block_id.clone());
     let lwd_err_status = lwd_client.get_tree_state(request).await.unwrap_err();
 
@@ -2857,36 +2437,15 @@ pub async fn get_latest_tree_state(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(proto::service::Empty {});
     let zainod_response = zainod_client
         .get_latest_tree_state(request)
@@ -2894,9 +2453,10 @@ pub async fn get_latest_tree_state(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(proto::service::Empty {});
     let lwd_response = lwd_client
         .get_latest_tree_state(request)
@@ -2943,31 +2503,22 @@ pub async fn get_subtree_roots_sapling(
         panic!("this test fixture requires testnet or mainnet network!");
     }
 
-    let zebrad = Zebrad::launch(ZebradConfig {
-        zebrad_bin,
-        network_listen_port: None,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: ZEBRAD_DEFAULT_MINER,
-        chain_cache: Some(utils::chain_cache_dir().join("get_subtree_roots_sapling")),
-        network,
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zebrad.rpc_listen_port(),
-        chain_cache: Some(utils::chain_cache_dir().join("get_subtree_roots_sapling")),
-        network,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zebrad.config_dir().path().join(config::ZCASHD_FILENAME),
-    })
-    .unwrap();
+    let chain_cache = utils::chain_cache_dir().join("get_subtree_roots_sapling");
+    let mut local_net = LocalNet::<Zainod, Zebrad>::builder()
+        .validator_config(|config| {
+            config.zebrad_bin = zebrad_bin;
+            config.network = network;
+        })
+        .indexer_config(|config| {
+            config.zainod_bin = zainod_bin;
+            config.chain_cache = Some(chain_cache.clone());
+            config.network = network;
+        })
+        .chain_cache(chain_cache)
+        .launch()
+        .await
+        .unwrap();
+    attach_lightwalletd(&mut local_net, lightwalletd_bin).await;
 
     let subtree_roots_arg = proto::service::GetSubtreeRootsArg {
         start_index: 0,
@@ -2975,9 +2526,10 @@ pub async fn get_subtree_roots_sapling(
         max_entries: 0,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(subtree_roots_arg);
     let mut zainod_response = zainod_client
         .get_subtree_roots(request)
@@ -2989,9 +2541,10 @@ pub async fn get_subtree_roots_sapling(
         zainod_subtree_roots.push(subtree_root);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(subtree_roots_arg);
     let mut lwd_response = lwd_client
         .get_subtree_roots(request)
@@ -3046,31 +2599,22 @@ pub async fn get_subtree_roots_orchard(
         panic!("this test fixture requires testnet or mainnet network!");
     }
 
-    let zebrad = Zebrad::launch(ZebradConfig {
-        zebrad_bin,
-        network_listen_port: None,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: ZEBRAD_DEFAULT_MINER,
-        chain_cache: Some(utils::chain_cache_dir().join("get_subtree_roots_orchard")),
-        network,
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zebrad.rpc_listen_port(),
-        chain_cache: Some(utils::chain_cache_dir().join("get_subtree_roots_orchard")),
-        network,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zebrad.config_dir().path().join(config::ZCASHD_FILENAME),
-    })
-    .unwrap();
+    let chain_cache = utils::chain_cache_dir().join("get_subtree_roots_orchard");
+    let mut local_net = LocalNet::<Zainod, Zebrad>::builder()
+        .validator_config(|config| {
+            config.zebrad_bin = zebrad_bin;
+            config.network = network;
+        })
+        .indexer_config(|config| {
+            config.zainod_bin = zainod_bin;
+            config.chain_cache = Some(chain_cache.clone());
+            config.network = network;
+        })
+        .chain_cache(chain_cache)
+        .launch()
+        .await
+        .unwrap();
+    attach_lightwalletd(&mut local_net, lightwalletd_bin).await;
 
     let subtree_roots_arg = proto::service::GetSubtreeRootsArg {
         start_index: 0,
@@ -3078,9 +2622,10 @@ pub async fn get_subtree_roots_orchard(
         max_entries: 0,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(subtree_roots_arg);
     let mut zainod_response = zainod_client
         .get_subtree_roots(request)
@@ -3092,9 +2637,10 @@ pub async fn get_subtree_roots_orchard(
         zainod_subtree_roots.push(subtree_root);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(subtree_roots_arg);
     let mut lwd_response = lwd_client
         .get_subtree_roots(request)
@@ -3129,30 +2675,8 @@ pub async fn get_address_utxos_all(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -3165,9 +2689,10 @@ pub async fn get_address_utxos_all(
         max_entries: 0,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let zainod_response = zainod_client
         .get_address_utxos(request)
@@ -3175,9 +2700,10 @@ pub async fn get_address_utxos_all(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let lwd_response = lwd_client
         .get_address_utxos(request)
@@ -3206,30 +2732,8 @@ pub async fn get_address_utxos_lower(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -3242,9 +2746,10 @@ pub async fn get_address_utxos_lower(
         max_entries: 1,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let zainod_response = zainod_client
         .get_address_utxos(request)
@@ -3252,9 +2757,10 @@ pub async fn get_address_utxos_lower(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let lwd_response = lwd_client
         .get_address_utxos(request)
@@ -3284,30 +2790,8 @@ pub async fn get_address_utxos_upper(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -3320,9 +2804,10 @@ pub async fn get_address_utxos_upper(
         max_entries: 1,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let zainod_response = zainod_client
         .get_address_utxos(request)
@@ -3330,9 +2815,10 @@ pub async fn get_address_utxos_upper(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let lwd_response = lwd_client
         .get_address_utxos(request)
@@ -3362,30 +2848,8 @@ pub async fn get_address_utxos_out_of_bounds(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -3398,9 +2862,10 @@ pub async fn get_address_utxos_out_of_bounds(
         max_entries: 0,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let zainod_response = zainod_client
         .get_address_utxos(request)
@@ -3408,9 +2873,10 @@ pub async fn get_address_utxos_out_of_bounds(
         .unwrap()
         .into_inner();
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let lwd_response = lwd_client
         .get_address_utxos(request)
@@ -3439,30 +2905,8 @@ pub async fn get_address_utxos_stream_all(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -3475,9 +2919,10 @@ pub async fn get_address_utxos_stream_all(
         max_entries: 0,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let mut zainod_response = zainod_client
         .get_address_utxos_stream(request)
@@ -3489,9 +2934,10 @@ pub async fn get_address_utxos_stream_all(
         zainod_address_utxo_replies.push(address_utxo_reply);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let mut lwd_response = lwd_client
         .get_address_utxos_stream(request)
@@ -3524,30 +2970,8 @@ pub async fn get_address_utxos_stream_lower(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -3560,9 +2984,10 @@ pub async fn get_address_utxos_stream_lower(
         max_entries: 1,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let mut zainod_response = zainod_client
         .get_address_utxos_stream(request)
@@ -3574,9 +2999,10 @@ pub async fn get_address_utxos_stream_lower(
         zainod_address_utxo_replies.push(address_utxo_reply);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let mut lwd_response = lwd_client
         .get_address_utxos_stream(request)
@@ -3610,30 +3036,8 @@ pub async fn get_address_utxos_stream_upper(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -3646,9 +3050,10 @@ pub async fn get_address_utxos_stream_upper(
         max_entries: 1,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let mut zainod_response = zainod_client
         .get_address_utxos_stream(request)
@@ -3660,9 +3065,10 @@ pub async fn get_address_utxos_stream_upper(
         zainod_address_utxo_replies.push(address_utxo_reply);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let mut lwd_response = lwd_client
         .get_address_utxos_stream(request)
@@ -3696,30 +3102,8 @@ pub async fn get_address_utxos_stream_out_of_bounds(
     zainod_bin: Option<PathBuf>,
     lightwalletd_bin: Option<PathBuf>,
 ) {
-    let zcashd = Zcashd::launch(ZcashdConfig {
-        zcashd_bin,
-        zcash_cli_bin,
-        rpc_listen_port: None,
-        activation_heights: network::ActivationHeights::default(),
-        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
-        chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests")),
-    })
-    .await
-    .unwrap();
-    let zainod = Zainod::launch(ZainodConfig {
-        zainod_bin,
-        listen_port: None,
-        validator_port: zcashd.port(),
-        chain_cache: None,
-        network: network::Network::Regtest,
-    })
-    .unwrap();
-    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
-        lightwalletd_bin,
-        listen_port: None,
-        zcashd_conf: zcashd.config_path(),
-    })
-    .unwrap();
+    let local_net =
+        launch_client_rpc_local_net(zcashd_bin, zcash_cli_bin, zainod_bin, lightwalletd_bin).await;
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
@@ -3732,9 +3116,10 @@ pub async fn get_address_utxos_stream_out_of_bounds(
         max_entries: 0,
     };
 
-    let mut zainod_client = client::build_client(network::localhost_uri(zainod.port()))
-        .await
-        .unwrap();
+    let mut zainod_client =
+        client::build_client(network::localhost_uri(local_net.indexer().port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let mut zainod_response = zainod_client
         .get_address_utxos_stream(request)
@@ -3746,9 +3131,10 @@ pub async fn get_address_utxos_stream_out_of_bounds(
         zainod_address_utxo_replies.push(address_utxo_reply);
     }
 
-    let mut lwd_client = client::build_client(network::localhost_uri(lightwalletd.port()))
-        .await
-        .unwrap();
+    let mut lwd_client =
+        client::build_client(network::localhost_uri(lightwalletd(&local_net).port()))
+            .await
+            .unwrap();
     let request = tonic::Request::new(address_utxos_arg.clone());
     let mut lwd_response = lwd_client
         .get_address_utxos_stream(request)
@@ -3775,3 +3161,49 @@ pub async fn get_address_utxos_stream_out_of_bounds(
     assert_eq!(lwd_address_utxo_replies.len(), 0);
     assert_eq!(zainod_address_utxo_replies, lwd_address_utxo_replies);
 }
+
+/// Launches Zcashd from the client RPC test chain cache with Zainod as the indexer and attaches Lightwalletd, for
+/// comparing the responses of both indexers.
+async fn launch_client_rpc_local_net(
+    zcashd_bin: Option<PathBuf>,
+    zcash_cli_bin: Option<PathBuf>,
+    zainod_bin: Option<PathBuf>,
+    lightwalletd_bin: Option<PathBuf>,
+) -> LocalNet<Zainod, Zcashd> {
+    let mut local_net = LocalNet::<Zainod, Zcashd>::builder()
+        .validator_config(|config| {
+            config.zcashd_bin = zcashd_bin;
+            config.zcash_cli_bin = zcash_cli_bin;
+        })
+        .indexer_config(|config| config.zainod_bin = zainod_bin)
+        .chain_cache(utils::chain_cache_dir().join("client_rpc_tests"))
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .launch()
+        .await
+        .unwrap();
+    attach_lightwalletd(&mut local_net, lightwalletd_bin).await;
+
+    local_net
+}
+
+/// Attaches Lightwalletd to the `local_net`, see [`lightwalletd`].
+async fn attach_lightwalletd<V: Validator>(
+    local_net: &mut LocalNet<Zainod, V>,
+    lightwalletd_bin: Option<PathBuf>,
+) {
+    local_net
+        .attach_indexer(AnyIndexerConfig::Lightwalletd(LightwalletdConfig {
+            lightwalletd_bin,
+            ..LightwalletdConfig::default()
+        }))
+        .await
+        .unwrap();
+}
+
+/// Returns the Lightwalletd indexer attached to the `local_net` by [`attach_lightwalletd`].
+fn lightwalletd<V: Validator>(local_net: &LocalNet<Zainod, V>) -> &Lightwalletd {
+    local_net
+        .additional_lightwalletds()
+        .next()
+        .expect("lightwalletd should be attached")
+}
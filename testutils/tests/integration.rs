@@ -1,17 +1,34 @@
 use std::path::PathBuf;
 
-use zcash_protocol::{PoolType, ShieldedProtocol};
+use zcash_protocol::{value::Zatoshis, PoolType, ShieldedProtocol};
 
-use testvectors::REG_O_ADDR_FROM_ABANDONART;
+use testvectors::{
+    REG_O_ADDR_FROM_ABANDONART, REG_T_ADDR_FROM_ABANDONART, REG_Z_ADDR_FROM_ABANDONART,
+};
 use zingolib::testutils::lightclient::{from_inputs, get_base_address};
 
-use zingo_infra_testutils::client;
+use zingo_infra_testutils::{
+    client::{self, LightClientConfig, LocalNetWithClients},
+    darkside,
+};
 
 use zingo_infra_services::{
-    indexer::{Indexer as _, Lightwalletd, LightwalletdConfig, Zainod, ZainodConfig},
-    network, utils,
-    validator::{Validator, Zcashd, ZcashdConfig, Zebrad, ZebradConfig, ZEBRAD_DEFAULT_MINER},
-    LocalNet,
+    block,
+    error::{
+        FundError, GenerateBlocksError, LaunchError, RpcError, StateCacheError, SubmitBlockError,
+    },
+    funding,
+    indexer::{
+        self, AnyIndexer, AnyIndexerConfig, Empty, EmptyConfig, Indexer, Lightwalletd,
+        LightwalletdConfig, Zainod, ZainodBackend, ZainodConfig,
+    },
+    network,
+    producer::BlockProduction,
+    rpc, utils,
+    validator::{
+        MempoolPolicy, Validator, Zcashd, ZcashdConfig, Zebrad, ZebradConfig, ZEBRAD_DEFAULT_MINER,
+    },
+    LocalNet, INDEXER_SYNC_TIMEOUT,
 };
 
 const ZCASHD_BIN: Option<PathBuf> = None;
@@ -20,6 +37,48 @@ const ZEBRAD_BIN: Option<PathBuf> = None;
 const LIGHTWALLETD_BIN: Option<PathBuf> = None;
 const ZAINOD_BIN: Option<PathBuf> = None;
 
+/// Regtest Zcashd config mining to the abandon art Orchard address.
+fn zcashd_config() -> ZcashdConfig {
+    ZcashdConfig {
+        miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
+        ..ZcashdConfig::default()
+    }
+}
+
+/// Generates a Zcashd and a Zebrad test calling each `assert` helper with a freshly launched regtest validator.
+macro_rules! validator_tests {
+    ($($assert:ident: $zcashd_test:ident, $zebrad_test:ident;)*) => {
+        $(
+            #[tokio::test]
+            async fn $zcashd_test() {
+                tracing_subscriber::fmt().init();
+
+                let mut zcashd = Zcashd::launch(zcashd_config()).await.unwrap();
+
+                $assert(&mut zcashd).await;
+            }
+
+            #[tokio::test]
+            async fn $zebrad_test() {
+                tracing_subscriber::fmt().init();
+
+                let mut zebrad = Zebrad::launch(ZebradConfig::default()).await.unwrap();
+
+                $assert(&mut zebrad).await;
+            }
+        )*
+    };
+}
+
+validator_tests! {
+    assert_rpc_call: zcashd_rpc_call, zebrad_rpc_call;
+    assert_typed_rpcs: zcashd_typed_rpcs, zebrad_typed_rpcs;
+    assert_submit_block_duplicate: zcashd_submit_block_duplicate, zebrad_submit_block_duplicate;
+    assert_checkpoint_rollback: zcashd_checkpoint_rollback, zebrad_checkpoint_rollback;
+    assert_block_production: zcashd_block_production, zebrad_block_production;
+    assert_block_timestamps: zcashd_block_timestamps, zebrad_block_timestamps;
+}
+
 #[tokio::test]
 async fn launch_zcashd() {
     tracing_subscriber::fmt().init();
@@ -31,6 +90,7 @@ async fn launch_zcashd() {
         activation_heights: network::ActivationHeights::default(),
         miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
         chain_cache: None,
+        network: network::Network::Regtest,
     })
     .await
     .unwrap();
@@ -50,6 +110,9 @@ async fn launch_zebrad() {
         miner_address: ZEBRAD_DEFAULT_MINER,
         chain_cache: None,
         network: network::Network::Regtest,
+        metrics: false,
+        tracing_filter: None,
+        tracing_endpoint: false,
     })
     .await
     .unwrap();
@@ -57,6 +120,357 @@ async fn launch_zebrad() {
     zebrad.print_stderr();
 }
 
+/// Asserts raw RPC calls to a freshly launched `validator` return the result or the RPC error.
+async fn assert_rpc_call<V: Validator>(validator: &V) {
+    let blockchain_info = validator
+        .rpc_call("getblockchaininfo", serde_json::json!([]))
+        .await
+        .unwrap();
+    assert_eq!(blockchain_info["blocks"], 1);
+
+    let error = validator
+        .rpc_call("getblock", serde_json::json!(["1000", 0]))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, RpcError::Response { .. }));
+}
+
+#[tokio::test]
+async fn zebrad_metrics_and_tracing_endpoints() {
+    tracing_subscriber::fmt().init();
+
+    let zebrad = Zebrad::launch(ZebradConfig {
+        metrics: true,
+        tracing_filter: Some("info".to_string()),
+        tracing_endpoint: true,
+        ..ZebradConfig::default()
+    })
+    .await
+    .unwrap();
+
+    zebrad.generate_blocks(2).await.unwrap();
+    let chain_height = zebrad.get_chain_height().await.unwrap();
+
+    // metrics are recorded asynchronously after blocks are committed
+    let start = std::time::Instant::now();
+    while zebrad.metrics().await.unwrap().block_height() != Some(chain_height) {
+        assert!(start.elapsed() < std::time::Duration::from_secs(30));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    zebrad.set_tracing_filter("debug").await.unwrap();
+    assert_eq!(zebrad.tracing_filter().await.unwrap().trim(), "debug");
+}
+
+/// Cross-checks the typed block, transaction and mempool RPCs of a freshly launched `validator`.
+async fn assert_typed_rpcs<V: Validator>(validator: &V) {
+    validator.generate_blocks(2).await.unwrap();
+    let chain_height = validator.get_chain_height().await.unwrap();
+
+    let best_block_hash = validator.get_best_block_hash().await.unwrap();
+    let block_by_hash = validator
+        .get_block(block::BlockId::Hash(best_block_hash))
+        .await
+        .unwrap();
+    let block_by_height = validator
+        .get_block(block::BlockId::Height(chain_height))
+        .await
+        .unwrap();
+    assert_eq!(block_by_hash.hash(), best_block_hash);
+    assert_eq!(block_by_hash.height(), chain_height);
+    assert_eq!(block_by_height.hash(), best_block_hash);
+
+    let coinbase = &block_by_height.transactions()[0];
+    let raw_coinbase = validator
+        .get_raw_transaction(coinbase.txid())
+        .await
+        .unwrap();
+    assert_eq!(raw_coinbase.txid(), coinbase.txid());
+
+    assert!(validator.get_raw_mempool().await.unwrap().is_empty());
+    assert_eq!(validator.mempool_size().await.unwrap(), 0);
+}
+
+/// Asserts a `validator` rejects resubmission of a block already in the chain.
+async fn assert_submit_block_duplicate<V: Validator>(validator: &V) {
+    let best_block_hash = validator.get_best_block_hash().await.unwrap();
+    let block_data = validator
+        .rpc_call(
+            "getblock",
+            serde_json::json!([best_block_hash.to_string(), 0]),
+        )
+        .await
+        .unwrap();
+    let block = hex::decode(block_data.as_str().unwrap()).unwrap();
+
+    let error = validator.submit_block(&block).await.unwrap_err();
+    assert!(matches!(
+        error,
+        GenerateBlocksError::SubmitBlock(SubmitBlockError::Duplicate)
+    ));
+}
+
+/// Asserts a `validator` is restored to the checkpointed chain state on rollback.
+async fn assert_checkpoint_rollback<V: Validator>(validator: &mut V) {
+    validator.generate_blocks(2).await.unwrap();
+    let checkpoint_height = validator.get_chain_height().await.unwrap();
+    let checkpoint_hash = validator.get_best_block_hash().await.unwrap();
+
+    let checkpoint = validator.checkpoint().await.unwrap();
+    assert_eq!(
+        validator.get_chain_height().await.unwrap(),
+        checkpoint_height
+    );
+
+    validator.generate_blocks(3).await.unwrap();
+    assert_eq!(
+        validator.get_chain_height().await.unwrap(),
+        checkpoint_height + 3
+    );
+
+    validator.rollback(&checkpoint).await.unwrap();
+    assert_eq!(
+        validator.get_chain_height().await.unwrap(),
+        checkpoint_height
+    );
+    assert_eq!(
+        validator.get_best_block_hash().await.unwrap(),
+        checkpoint_hash
+    );
+}
+
+/// Asserts a `validator` funds the `pool` receiver of `address` with a mined transaction.
+async fn assert_fund<V: Validator>(validator: &V, address: &str, pool: PoolType) {
+    let amount = Zatoshis::from_u64(100_000).unwrap();
+
+    let txid = validator.fund(address, amount, pool).await.unwrap();
+    assert!(validator.get_raw_mempool().await.unwrap().is_empty());
+
+    let transaction = validator.get_raw_transaction(txid).await.unwrap();
+    match pool {
+        PoolType::Transparent => assert!(transaction
+            .transparent_bundle()
+            .unwrap()
+            .vout
+            .iter()
+            .any(|output| output.value == amount)),
+        PoolType::Shielded(ShieldedProtocol::Sapling) => {
+            assert!(transaction.sapling_bundle().is_some())
+        }
+        PoolType::Shielded(ShieldedProtocol::Orchard) => {
+            assert!(transaction.orchard_bundle().is_some())
+        }
+    }
+}
+
+#[tokio::test]
+async fn zcashd_fund() {
+    tracing_subscriber::fmt().init();
+
+    // mining rewards must be paid to the zcashd wallet for funding
+    let zcashd = Zcashd::launch(ZcashdConfig {
+        zcashd_bin: ZCASHD_BIN,
+        zcash_cli_bin: ZCASH_CLI_BIN,
+        rpc_listen_port: None,
+        activation_heights: network::ActivationHeights::default(),
+        miner_address: None,
+        chain_cache: None,
+        network: network::Network::Regtest,
+    })
+    .await
+    .unwrap();
+
+    assert_fund(&zcashd, REG_T_ADDR_FROM_ABANDONART, PoolType::Transparent).await;
+    assert_fund(
+        &zcashd,
+        REG_Z_ADDR_FROM_ABANDONART,
+        PoolType::Shielded(ShieldedProtocol::Sapling),
+    )
+    .await;
+    assert_fund(
+        &zcashd,
+        REG_O_ADDR_FROM_ABANDONART,
+        PoolType::Shielded(ShieldedProtocol::Orchard),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn zebrad_fund() {
+    tracing_subscriber::fmt().init();
+
+    let zebrad = Zebrad::launch(ZebradConfig::default()).await.unwrap();
+
+    assert_fund(
+        &zebrad,
+        REG_O_ADDR_FROM_ABANDONART,
+        PoolType::Shielded(ShieldedProtocol::Orchard),
+    )
+    .await;
+
+    let error = zebrad
+        .fund(
+            REG_T_ADDR_FROM_ABANDONART,
+            Zatoshis::from_u64(100_000).unwrap(),
+            PoolType::Transparent,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        FundError::UnsupportedPool(PoolType::Transparent)
+    ));
+}
+
+/// Asserts a freshly launched `validator` mines until the first coinbase output paid to `miner_address` is spendable.
+async fn assert_mine_until_spendable<V: Validator>(validator: &V, miner_address: &str) {
+    let target_height = validator.get_chain_height().await.unwrap() + 3;
+    let chain_height = validator
+        .generate_blocks_until(|chain_height| chain_height >= target_height, 3)
+        .await
+        .unwrap();
+    assert_eq!(chain_height, target_height);
+
+    // generation stops with an error once the maximum number of blocks is reached
+    assert!(matches!(
+        validator.generate_blocks_until(|_| false, 2).await,
+        Err(GenerateBlocksError::MaxBlocks(2))
+    ));
+    assert_eq!(
+        validator.get_chain_height().await.unwrap(),
+        target_height + 2
+    );
+
+    // first coinbase output is mined at height 1 with the default activation heights
+    let spendable_height = validator.mine_until_spendable(miner_address).await.unwrap();
+    assert_eq!(spendable_height, (1 + funding::COINBASE_MATURITY).into());
+    assert_eq!(
+        validator.get_chain_height().await.unwrap(),
+        spendable_height
+    );
+
+    // no blocks are generated once the coinbase output is spendable
+    assert_eq!(
+        validator.mine_until_spendable(miner_address).await.unwrap(),
+        spendable_height
+    );
+}
+
+#[tokio::test]
+async fn zcashd_mine_until_spendable() {
+    tracing_subscriber::fmt().init();
+
+    let zcashd = Zcashd::launch(zcashd_config()).await.unwrap();
+
+    assert_mine_until_spendable(&zcashd, REG_O_ADDR_FROM_ABANDONART).await;
+}
+
+#[tokio::test]
+async fn zebrad_mine_until_spendable() {
+    tracing_subscriber::fmt().init();
+
+    let zebrad = Zebrad::launch(ZebradConfig::default()).await.unwrap();
+
+    assert_mine_until_spendable(&zebrad, ZEBRAD_DEFAULT_MINER).await;
+}
+
+/// Asserts blocks are produced in the background by the `validator` until block production is stopped.
+async fn assert_block_production<V: Validator>(validator: &V) {
+    let start_height = validator.get_chain_height().await.unwrap();
+    let producer = validator.start_block_production(BlockProduction::Interval(
+        std::time::Duration::from_millis(500),
+    ));
+    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+    assert!(producer.is_running());
+    let blocks_produced = producer.stop().await.unwrap();
+    assert!(blocks_produced > 0);
+    assert_eq!(
+        validator.get_chain_height().await.unwrap(),
+        start_height + blocks_produced
+    );
+
+    // no blocks are produced while the mempool is empty
+    let start_height = validator.get_chain_height().await.unwrap();
+    let producer = validator.start_block_production(BlockProduction::MempoolArrival);
+    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+    assert_eq!(producer.stop().await.unwrap(), 0);
+    assert_eq!(validator.get_chain_height().await.unwrap(), start_height);
+}
+
+#[tokio::test]
+async fn zebrad_network_upgrade_boundaries() {
+    tracing_subscriber::fmt().init();
+
+    // canopy and all earlier network upgrades must be active from height 1 in zebrad regtest mode
+    let activation_heights = network::ActivationHeights {
+        nu5: 3.into(),
+        nu6: 5.into(),
+        ..network::ActivationHeights::default()
+    };
+    let zebrad = Zebrad::launch(ZebradConfig {
+        activation_heights,
+        ..ZebradConfig::default()
+    })
+    .await
+    .unwrap();
+
+    // blocks built for the wrong network upgrade are rejected by zebrad
+    let chain_height = zebrad.get_chain_height().await.unwrap();
+    zebrad.generate_blocks(6).await.unwrap();
+    let final_height = chain_height + 6;
+    assert_eq!(zebrad.get_chain_height().await.unwrap(), final_height);
+
+    for height in 1..=u32::from(final_height) {
+        let block = zebrad
+            .get_block(block::BlockId::Height(height.into()))
+            .await
+            .unwrap();
+        assert_eq!(
+            block.transactions()[0].consensus_branch_id(),
+            activation_heights.branch_id(height.into())
+        );
+    }
+}
+
+/// Asserts blocks generated by a freshly launched `validator` have the requested timestamps.
+async fn assert_block_timestamps<V: Validator>(validator: &V) {
+    const INTERVAL: u32 = 75;
+
+    // timestamps must be later than the median time of previous blocks and no later than the template's max time
+    let start = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as u32
+        + 60;
+    let start_height = validator.get_chain_height().await.unwrap() + 1;
+    validator
+        .generate_blocks_with_interval(3, start, INTERVAL)
+        .await
+        .unwrap();
+
+    for i in 0..3 {
+        let block = validator
+            .get_block(block::BlockId::Height(start_height + i))
+            .await
+            .unwrap();
+        assert_eq!(block.header().time, start + i * INTERVAL);
+    }
+}
+
+#[tokio::test]
+async fn zebrad_block_timestamp_out_of_range() {
+    tracing_subscriber::fmt().init();
+
+    let zebrad = Zebrad::launch(ZebradConfig::default()).await.unwrap();
+    let chain_height = zebrad.get_chain_height().await.unwrap();
+
+    assert!(matches!(
+        zebrad.generate_block_at(1).await,
+        Err(GenerateBlocksError::InvalidTimestamp(_))
+    ));
+    assert_eq!(zebrad.get_chain_height().await.unwrap(), chain_height);
+}
+
 #[ignore = "temporary during refactor into workspace"]
 #[tokio::test]
 async fn launch_zebrad_with_cache() {
@@ -70,13 +484,94 @@ async fn launch_zebrad_with_cache() {
         miner_address: ZEBRAD_DEFAULT_MINER,
         chain_cache: Some(utils::chain_cache_dir().join("client_rpc_tests_large")),
         network: network::Network::Regtest,
+        metrics: false,
+        tracing_filter: None,
+        tracing_endpoint: false,
     })
     .await
     .unwrap();
     zebrad.print_stdout();
     zebrad.print_stderr();
 
-    assert_eq!(zebrad.get_chain_height().await, 52.into());
+    assert_eq!(zebrad.get_chain_height().await.unwrap(), 52.into());
+}
+
+/// To run this test, sync Zcashd in testnet mode and copy the data directory (containing `testnet3`) to
+/// `zcash_local_net/chain_cache/testnet_zcashd`.
+#[ignore = "requires a synced testnet chain cache"]
+#[tokio::test]
+async fn launch_zcashd_with_testnet_cache() {
+    tracing_subscriber::fmt().init();
+
+    let zcashd = Zcashd::launch(ZcashdConfig {
+        zcashd_bin: ZCASHD_BIN,
+        zcash_cli_bin: ZCASH_CLI_BIN,
+        rpc_listen_port: None,
+        activation_heights: network::ActivationHeights::default(),
+        miner_address: None,
+        chain_cache: Some(utils::chain_cache_dir().join("testnet_zcashd")),
+        network: network::Network::Testnet,
+    })
+    .await
+    .unwrap();
+    zcashd.print_stdout();
+    zcashd.print_stderr();
+
+    assert!(matches!(zcashd.network(), network::Network::Testnet));
+    assert!(zcashd.get_chain_height().await.unwrap() > 0.into());
+}
+
+/// Asserts the `indexer` of a regtest local net serves light client gRPC requests at its gRPC URI.
+async fn assert_indexer_connection<I: Indexer>(indexer: &I) {
+    assert!(matches!(indexer.network(), Some(network::Network::Regtest)));
+    indexer.health_check().await.unwrap();
+
+    let mut client = client::build_client(indexer.grpc_uri().unwrap())
+        .await
+        .unwrap();
+    client
+        .get_lightd_info(tonic::Request::new(
+            zcash_client_backend::proto::service::Empty {},
+        ))
+        .await
+        .unwrap();
+}
+
+/// Asserts the indexer of the `local_net` serves the new chain tip as soon as generated blocks are synced.
+async fn assert_generate_blocks_and_sync<I: Indexer, V: Validator>(local_net: &LocalNet<I, V>) {
+    let chain_height = local_net.generate_blocks_and_sync(3).await.unwrap();
+    assert_eq!(
+        chain_height,
+        local_net.validator().get_chain_height().await.unwrap()
+    );
+
+    let mut client = client::build_client(local_net.indexer().grpc_uri().unwrap())
+        .await
+        .unwrap();
+    let latest_block = client
+        .get_latest_block(tonic::Request::new(
+            zcash_client_backend::proto::service::ChainSpec {},
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(latest_block.height, u64::from(chain_height));
+}
+
+#[tokio::test]
+async fn empty_indexer() {
+    tracing_subscriber::fmt().init();
+
+    let empty = Empty::launch(EmptyConfig {}).await.unwrap();
+
+    assert_eq!(empty.grpc_port(), None);
+    assert_eq!(empty.grpc_uri(), None);
+    assert!(empty.network().is_none());
+    empty.health_check().await.unwrap();
+    empty
+        .wait_for_height(100.into(), std::time::Duration::ZERO)
+        .await
+        .unwrap();
 }
 
 #[tokio::test]
@@ -87,9 +582,12 @@ async fn launch_localnet_zainod_zcashd() {
         ZainodConfig {
             zainod_bin: ZAINOD_BIN,
             listen_port: None,
+            enable_json_rpc: false,
+            json_rpc_listen_port: None,
             validator_port: 0,
             chain_cache: None,
             network: network::Network::Regtest,
+            backend: ZainodBackend::Fetch,
         },
         ZcashdConfig {
             zcashd_bin: ZCASHD_BIN,
@@ -98,9 +596,14 @@ async fn launch_localnet_zainod_zcashd() {
             activation_heights: network::ActivationHeights::default(),
             miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
             chain_cache: None,
+            network: network::Network::Regtest,
         },
     )
-    .await;
+    .await
+    .unwrap();
+
+    assert_indexer_connection(local_net.indexer()).await;
+    assert_generate_blocks_and_sync(&local_net).await;
 
     local_net.validator().print_stdout();
     local_net.validator().print_stderr();
@@ -116,9 +619,12 @@ async fn launch_localnet_zainod_zebrad() {
         ZainodConfig {
             zainod_bin: ZAINOD_BIN,
             listen_port: None,
+            enable_json_rpc: false,
+            json_rpc_listen_port: None,
             validator_port: 0,
             chain_cache: None,
             network: network::Network::Regtest,
+            backend: ZainodBackend::Fetch,
         },
         ZebradConfig {
             zebrad_bin: ZEBRAD_BIN,
@@ -128,9 +634,16 @@ async fn launch_localnet_zainod_zebrad() {
             miner_address: ZEBRAD_DEFAULT_MINER,
             chain_cache: None,
             network: network::Network::Regtest,
+            metrics: false,
+            tracing_filter: None,
+            tracing_endpoint: false,
         },
     )
-    .await;
+    .await
+    .unwrap();
+
+    assert_indexer_connection(local_net.indexer()).await;
+    assert_generate_blocks_and_sync(&local_net).await;
 
     local_net.validator().print_stdout();
     local_net.validator().print_stderr();
@@ -138,6 +651,174 @@ async fn launch_localnet_zainod_zebrad() {
     local_net.indexer().print_stderr();
 }
 
+#[tokio::test]
+async fn launch_localnet_zainod_zebrad_state_service() {
+    tracing_subscriber::fmt().init();
+
+    let mut local_net = LocalNet::<Zainod, Zebrad>::builder()
+        .indexer_config(|config| {
+            config.backend = ZainodBackend::State {
+                zebrad_cache_dir: PathBuf::new(),
+            }
+        })
+        .launch()
+        .await
+        .unwrap();
+
+    assert_indexer_connection(local_net.indexer()).await;
+
+    let chain_height = local_net.generate_blocks_and_sync(2).await.unwrap();
+
+    let mut zainod_client = client::build_client(local_net.indexer().grpc_uri().unwrap())
+        .await
+        .unwrap();
+    let latest_block = zainod_client
+        .get_latest_block(tonic::Request::new(
+            zcash_client_backend::proto::service::ChainSpec {},
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(latest_block.height, u64::from(chain_height));
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        local_net
+            .indexer_mut()
+            .cache_state(cache_dir.path().join("state_cache"))
+            .await,
+        Err(StateCacheError::Unsupported(_))
+    ));
+}
+
+#[tokio::test]
+async fn lightwalletd_darkside() {
+    tracing_subscriber::fmt().init();
+
+    let lightwalletd = Lightwalletd::launch(LightwalletdConfig {
+        darkside: true,
+        ..LightwalletdConfig::default()
+    })
+    .await
+    .unwrap();
+    assert!(matches!(
+        lightwalletd.network(),
+        Some(network::Network::Regtest)
+    ));
+
+    let mut darkside_client =
+        darkside::DarksideStreamerClient::connect(lightwalletd.grpc_uri().unwrap())
+            .await
+            .unwrap();
+    darkside_client
+        .reset(darkside::DarksideMetaState::regtest(1))
+        .await
+        .unwrap();
+    darkside_client.stage_blocks_create(1, 0, 10).await.unwrap();
+    darkside_client.apply_staged(10).await.unwrap();
+    darkside_client
+        .add_tree_state(zcash_client_backend::proto::service::TreeState {
+            network: "regtest".to_string(),
+            height: 5,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let mut lwd_client = client::build_client(lightwalletd.grpc_uri().unwrap())
+        .await
+        .unwrap();
+    let latest_block = lwd_client
+        .get_latest_block(tonic::Request::new(
+            zcash_client_backend::proto::service::ChainSpec {},
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(latest_block.height, 10);
+    let tree_state = lwd_client
+        .get_tree_state(tonic::Request::new(
+            zcash_client_backend::proto::service::BlockId {
+                height: 5,
+                hash: Vec::new(),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(tree_state.height, 5);
+
+    // reorg the chain from height 6 by staging blocks with a different nonce
+    let block_id = |height| zcash_client_backend::proto::service::BlockId {
+        height,
+        hash: Vec::new(),
+    };
+    let block_5 = lwd_client
+        .get_block(tonic::Request::new(block_id(5)))
+        .await
+        .unwrap()
+        .into_inner();
+    let block_8 = lwd_client
+        .get_block(tonic::Request::new(block_id(8)))
+        .await
+        .unwrap()
+        .into_inner();
+
+    darkside_client.stage_blocks_create(6, 1, 5).await.unwrap();
+    darkside_client.apply_staged(10).await.unwrap();
+
+    let reorged_block_5 = lwd_client
+        .get_block(tonic::Request::new(block_id(5)))
+        .await
+        .unwrap()
+        .into_inner();
+    let reorged_block_8 = lwd_client
+        .get_block(tonic::Request::new(block_id(8)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(reorged_block_5.hash, block_5.hash);
+    assert_ne!(reorged_block_8.hash, block_8.hash);
+}
+
+#[tokio::test]
+async fn lightwalletd_missing_zcashd_conf() {
+    tracing_subscriber::fmt().init();
+
+    assert!(matches!(
+        Lightwalletd::launch(LightwalletdConfig::default()).await,
+        Err(LaunchError::MissingZcashdConf)
+    ));
+}
+
+#[tokio::test]
+async fn zcashd_missing_binary() {
+    tracing_subscriber::fmt().init();
+
+    assert!(matches!(
+        Zcashd::launch(ZcashdConfig {
+            zcashd_bin: Some(PathBuf::from("/nonexistent/zcashd")),
+            ..zcashd_config()
+        })
+        .await,
+        Err(LaunchError::Spawn { .. })
+    ));
+}
+
+#[tokio::test]
+async fn zebrad_missing_chain_cache() {
+    tracing_subscriber::fmt().init();
+
+    assert!(matches!(
+        Zebrad::launch(ZebradConfig {
+            network: network::Network::Testnet,
+            ..ZebradConfig::default()
+        })
+        .await,
+        Err(LaunchError::MissingChainCache(network::Network::Testnet))
+    ));
+}
+
 #[tokio::test]
 async fn launch_localnet_lightwalletd_zcashd() {
     tracing_subscriber::fmt().init();
@@ -146,7 +827,8 @@ async fn launch_localnet_lightwalletd_zcashd() {
         LightwalletdConfig {
             lightwalletd_bin: LIGHTWALLETD_BIN,
             listen_port: None,
-            zcashd_conf: PathBuf::new(),
+            zcashd_conf: None,
+            darkside: false,
         },
         ZcashdConfig {
             zcashd_bin: ZCASHD_BIN,
@@ -155,9 +837,14 @@ async fn launch_localnet_lightwalletd_zcashd() {
             activation_heights: network::ActivationHeights::default(),
             miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
             chain_cache: None,
+            network: network::Network::Regtest,
         },
     )
-    .await;
+    .await
+    .unwrap();
+
+    assert_indexer_connection(local_net.indexer()).await;
+    assert_generate_blocks_and_sync(&local_net).await;
 
     local_net.validator().print_stdout();
     local_net.validator().print_stderr();
@@ -166,6 +853,47 @@ async fn launch_localnet_lightwalletd_zcashd() {
     local_net.indexer().print_stderr();
 }
 
+#[tokio::test]
+async fn localnet_lightwalletd_zcashd_checkpoint_rollback() {
+    tracing_subscriber::fmt().init();
+
+    let mut local_net = LocalNet::<Lightwalletd, Zcashd>::builder()
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .launch()
+        .await
+        .unwrap();
+
+    local_net.validator().generate_blocks(2).await.unwrap();
+    let checkpoint_height = local_net.validator().get_chain_height().await.unwrap();
+    let checkpoint = local_net.checkpoint().await.unwrap();
+
+    local_net.validator().generate_blocks(3).await.unwrap();
+    local_net
+        .attach_indexer(AnyIndexerConfig::Zainod(ZainodConfig {
+            zainod_bin: ZAINOD_BIN,
+            ..ZainodConfig::default()
+        }))
+        .await
+        .unwrap();
+    local_net.rollback(&checkpoint).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    // indexers attached after the checkpoint are stopped on rollback
+    assert!(local_net.additional_indexers().is_empty());
+
+    let mut lwd_client = client::build_client(network::localhost_uri(local_net.indexer().port()))
+        .await
+        .unwrap();
+    let latest_block = lwd_client
+        .get_latest_block(tonic::Request::new(
+            zcash_client_backend::proto::service::ChainSpec {},
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(latest_block.height, u64::from(checkpoint_height));
+}
+
 #[tokio::test]
 async fn launch_localnet_lightwalletd_zebrad() {
     tracing_subscriber::fmt().init();
@@ -174,7 +902,8 @@ async fn launch_localnet_lightwalletd_zebrad() {
         LightwalletdConfig {
             lightwalletd_bin: LIGHTWALLETD_BIN,
             listen_port: None,
-            zcashd_conf: PathBuf::new(),
+            zcashd_conf: None,
+            darkside: false,
         },
         ZebradConfig {
             zebrad_bin: ZEBRAD_BIN,
@@ -184,9 +913,16 @@ async fn launch_localnet_lightwalletd_zebrad() {
             miner_address: ZEBRAD_DEFAULT_MINER,
             chain_cache: None,
             network: network::Network::Regtest,
+            metrics: false,
+            tracing_filter: None,
+            tracing_endpoint: false,
         },
     )
-    .await;
+    .await
+    .unwrap();
+
+    assert_indexer_connection(local_net.indexer()).await;
+    assert_generate_blocks_and_sync(&local_net).await;
 
     local_net.validator().print_stdout();
     local_net.validator().print_stderr();
@@ -195,6 +931,291 @@ async fn launch_localnet_lightwalletd_zebrad() {
     local_net.indexer().print_stderr();
 }
 
+/// Asserts the JSON-RPC server of the Zainod indexer serves the same blocks as the validator.
+async fn assert_zainod_json_rpc<V: Validator>(local_net: &LocalNet<Zainod, V>) {
+    let chain_height = local_net.generate_blocks_and_sync(2).await.unwrap();
+    let indexer_client =
+        client::build_json_rpc_client(local_net.indexer().json_rpc_port().unwrap());
+
+    for height in 1..=u32::from(chain_height) {
+        let params = serde_json::json!([height.to_string(), 1]);
+        let indexer_block = indexer_client
+            .call("getblock", params.clone())
+            .await
+            .unwrap();
+        let validator_block = local_net
+            .validator()
+            .rpc_client()
+            .call("getblock", params)
+            .await
+            .unwrap();
+        assert_eq!(indexer_block["hash"], validator_block["hash"]);
+        assert_eq!(indexer_block["tx"], validator_block["tx"]);
+    }
+}
+
+#[tokio::test]
+async fn zainod_zcashd_json_rpc() {
+    tracing_subscriber::fmt().init();
+
+    let local_net = LocalNet::<Zainod, Zcashd>::builder()
+        .indexer_config(|config| config.enable_json_rpc = true)
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .launch()
+        .await
+        .unwrap();
+
+    assert_zainod_json_rpc(&local_net).await;
+
+    local_net.indexer().print_stdout();
+    local_net.indexer().print_stderr();
+}
+
+#[tokio::test]
+async fn zainod_zebrad_json_rpc() {
+    tracing_subscriber::fmt().init();
+
+    let local_net = LocalNet::<Zainod, Zebrad>::builder()
+        .indexer_config(|config| config.enable_json_rpc = true)
+        .launch()
+        .await
+        .unwrap();
+
+    assert_zainod_json_rpc(&local_net).await;
+
+    local_net.indexer().print_stdout();
+    local_net.indexer().print_stderr();
+}
+
+#[tokio::test]
+async fn zainod_state_cache() {
+    tracing_subscriber::fmt().init();
+
+    let zainod_config = |validator_port, chain_cache| ZainodConfig {
+        validator_port,
+        chain_cache,
+        ..ZainodConfig::default()
+    };
+
+    let mut local_net = LocalNet::<Zainod, Zcashd>::builder()
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .launch()
+        .await
+        .unwrap();
+    let chain_height = local_net.generate_blocks_and_sync(3).await.unwrap();
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let state_cache = cache_dir.path().join("zainod");
+    local_net
+        .indexer_mut()
+        .cache_state(state_cache.clone())
+        .await
+        .unwrap();
+    assert!(matches!(
+        local_net
+            .indexer_mut()
+            .cache_state(state_cache.clone())
+            .await,
+        Err(StateCacheError::AlreadyExists(_))
+    ));
+
+    // the cache records the chain tip served by the indexer
+    let metadata: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(state_cache.join(indexer::STATE_CACHE_METADATA_FILENAME)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(metadata["height"], u32::from(chain_height));
+    assert_eq!(
+        metadata["hash"],
+        local_net
+            .validator()
+            .rpc_call("getblockhash", serde_json::json!([u32::from(chain_height)]))
+            .await
+            .unwrap()
+    );
+
+    let zainod = Zainod::launch(zainod_config(
+        local_net.validator().port(),
+        Some(state_cache.clone()),
+    ))
+    .await
+    .unwrap();
+
+    // the cached state is loaded into the data directory instead of syncing from an empty state
+    for entry in std::fs::read_dir(&state_cache).unwrap() {
+        assert!(zainod
+            .data_dir()
+            .path()
+            .join(entry.unwrap().file_name())
+            .exists());
+    }
+    zainod
+        .wait_for_height(chain_height, INDEXER_SYNC_TIMEOUT)
+        .await
+        .unwrap();
+
+    // the cached chain tip is not in the chain of a new validator
+    let zcashd = Zcashd::launch(zcashd_config()).await.unwrap();
+    zcashd.generate_blocks(1).await.unwrap();
+    assert!(matches!(
+        Zainod::launch(zainod_config(zcashd.port(), Some(state_cache))).await,
+        Err(LaunchError::StateCache(error))
+            if matches!(*error, StateCacheError::ChainMismatch { .. })
+    ));
+}
+
+#[tokio::test]
+async fn launch_localnet_any_indexer_zebrad() {
+    tracing_subscriber::fmt().init();
+
+    let local_net = LocalNet::<AnyIndexer, Zebrad>::builder()
+        .indexer_config(|config| {
+            *config = AnyIndexerConfig::Lightwalletd(LightwalletdConfig::default())
+        })
+        .launch()
+        .await
+        .unwrap();
+
+    assert!(local_net.indexer().as_lightwalletd().is_some());
+    assert_indexer_connection(local_net.indexer()).await;
+    assert_generate_blocks_and_sync(&local_net).await;
+
+    local_net.validator().print_stdout();
+    local_net.validator().print_stderr();
+    local_net.indexer().print_stdout();
+    local_net.indexer().print_stderr();
+}
+
+#[tokio::test]
+async fn launch_localnet_builder() {
+    tracing_subscriber::fmt().init();
+
+    let activation_heights = network::ActivationHeights {
+        nu5: 2.into(),
+        nu6: 3.into(),
+        ..network::ActivationHeights::default()
+    };
+    let local_net = LocalNet::<Zainod, Zebrad>::builder()
+        .activation_heights(activation_heights)
+        .miner_address(ZEBRAD_DEFAULT_MINER)
+        .with_proxy()
+        .launch()
+        .await
+        .unwrap();
+
+    assert!(local_net.proxy().is_some());
+    assert_eq!(
+        local_net.validator().activation_heights(),
+        &activation_heights
+    );
+    assert_indexer_connection(local_net.indexer()).await;
+    assert_generate_blocks_and_sync(&local_net).await;
+}
+
+#[tokio::test]
+async fn launch_localnet_builder_failure() {
+    tracing_subscriber::fmt().init();
+
+    let rpc_listen_port = portpicker::pick_unused_port().unwrap();
+    let result = LocalNet::<Zainod, Zebrad>::builder()
+        .indexer_bin(PathBuf::from("false"))
+        .validator_config(|config| config.rpc_listen_port = Some(rpc_listen_port))
+        .launch()
+        .await;
+
+    assert!(matches!(result, Err(LaunchError::ProcessFailed { .. })));
+    // the validator is stopped when the indexer fails to launch
+    assert!(rpc::RpcClient::new(rpc_listen_port)
+        .call("getblockcount", serde_json::json!([]))
+        .await
+        .is_err());
+}
+
+/// Attaches a Lightwalletd indexer to the `local_net` and asserts it is connected to the validator and synced.
+async fn assert_attached_lightwalletd<V: Validator>(mut local_net: LocalNet<Zainod, V>) {
+    local_net
+        .attach_indexer(AnyIndexerConfig::Lightwalletd(LightwalletdConfig::default()))
+        .await
+        .unwrap();
+    assert_eq!(local_net.additional_indexers().len(), 1);
+    assert_eq!(local_net.additional_lightwalletds().count(), 1);
+    assert_eq!(local_net.additional_zainods().count(), 0);
+
+    for indexer in local_net.additional_indexers() {
+        assert_indexer_connection(indexer).await;
+    }
+    assert_generate_blocks_and_sync(&local_net).await;
+
+    let chain_height = local_net.validator().get_chain_height().await.unwrap();
+    let lightwalletd = local_net.additional_lightwalletds().next().unwrap();
+    let mut client = client::build_client(lightwalletd.grpc_uri().unwrap())
+        .await
+        .unwrap();
+    let latest_block = client
+        .get_latest_block(tonic::Request::new(
+            zcash_client_backend::proto::service::ChainSpec {},
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(latest_block.height, u64::from(chain_height));
+
+    local_net.validator().print_stdout();
+    local_net.validator().print_stderr();
+    local_net.indexer().print_stdout();
+    local_net.indexer().print_stderr();
+    lightwalletd.print_lwd_log();
+}
+
+#[tokio::test]
+async fn launch_localnet_zainod_zcashd_with_lightwalletd() {
+    tracing_subscriber::fmt().init();
+
+    let local_net = LocalNet::<Zainod, Zcashd>::builder()
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .launch()
+        .await
+        .unwrap();
+
+    assert_attached_lightwalletd(local_net).await;
+}
+
+#[tokio::test]
+async fn launch_localnet_zainod_zebrad_with_lightwalletd() {
+    tracing_subscriber::fmt().init();
+
+    let local_net = LocalNet::<Zainod, Zebrad>::builder()
+        .launch()
+        .await
+        .unwrap();
+
+    assert_attached_lightwalletd(local_net).await;
+}
+
+#[tokio::test]
+async fn launch_localnet_zainod_zcashd_with_proxy() {
+    tracing_subscriber::fmt().init();
+
+    let local_net = LocalNet::<Zainod, Zcashd>::builder()
+        .miner_address(REG_O_ADDR_FROM_ABANDONART)
+        .with_proxy()
+        .launch()
+        .await
+        .unwrap();
+
+    let proxy = local_net.proxy().unwrap();
+    proxy.set_latency(std::time::Duration::from_millis(200));
+    local_net.validator().generate_blocks(1).await.unwrap();
+    proxy.reset_connections();
+    proxy.clear_faults();
+    local_net.validator().generate_blocks(1).await.unwrap();
+
+    local_net.validator().print_stdout();
+    local_net.validator().print_stderr();
+    local_net.indexer().print_stdout();
+    local_net.indexer().print_stderr();
+}
+
 #[tokio::test]
 async fn zainod_zcashd_basic_send() {
     tracing_subscriber::fmt().init();
@@ -203,9 +1224,12 @@ async fn zainod_zcashd_basic_send() {
         ZainodConfig {
             zainod_bin: ZAINOD_BIN,
             listen_port: None,
+            enable_json_rpc: false,
+            json_rpc_listen_port: None,
             validator_port: 0,
             chain_cache: None,
             network: network::Network::Regtest,
+            backend: ZainodBackend::Fetch,
         },
         ZcashdConfig {
             zcashd_bin: ZCASHD_BIN,
@@ -214,14 +1238,17 @@ async fn zainod_zcashd_basic_send() {
             activation_heights: network::ActivationHeights::default(),
             miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
             chain_cache: None,
+            network: network::Network::Regtest,
         },
     )
-    .await;
+    .await
+    .unwrap();
 
     let lightclient_dir = tempfile::tempdir().unwrap();
     let (faucet, recipient) = client::build_lightclients(
         lightclient_dir.path().to_path_buf(),
         local_net.indexer().port(),
+        local_net.validator(),
     )
     .await;
     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
@@ -264,9 +1291,12 @@ async fn zainod_zebrad_basic_send() {
         ZainodConfig {
             zainod_bin: ZAINOD_BIN,
             listen_port: None,
+            enable_json_rpc: false,
+            json_rpc_listen_port: None,
             validator_port: 0,
             chain_cache: None,
             network: network::Network::Regtest,
+            backend: ZainodBackend::Fetch,
         },
         ZebradConfig {
             zebrad_bin: ZEBRAD_BIN,
@@ -276,14 +1306,19 @@ async fn zainod_zebrad_basic_send() {
             miner_address: ZEBRAD_DEFAULT_MINER,
             chain_cache: None,
             network: network::Network::Regtest,
+            metrics: false,
+            tracing_filter: None,
+            tracing_endpoint: false,
         },
     )
-    .await;
+    .await
+    .unwrap();
 
     let lightclient_dir = tempfile::tempdir().unwrap();
     let (faucet, recipient) = client::build_lightclients(
         lightclient_dir.path().to_path_buf(),
         local_net.indexer().port(),
+        local_net.validator(),
     )
     .await;
 
@@ -326,6 +1361,150 @@ async fn zainod_zebrad_basic_send() {
     println!("{:?}\n", recipient_balance);
 }
 
+#[tokio::test]
+async fn local_net_with_clients_basic_send() {
+    tracing_subscriber::fmt().init();
+
+    let local_net = LocalNet::<Zainod, Zebrad>::builder()
+        .launch()
+        .await
+        .unwrap();
+    let local_net = LocalNetWithClients::new(
+        local_net,
+        vec![LightClientConfig::faucet(), LightClientConfig::recipient()],
+    )
+    .await;
+    let faucet = local_net.lightclient("faucet").unwrap();
+    let recipient = local_net.lightclient("recipient").unwrap();
+
+    local_net.generate_blocks_and_sync(100).await;
+    faucet.quick_shield().await.unwrap();
+    local_net.generate_blocks_and_sync(1).await;
+
+    from_inputs::quick_send(
+        faucet,
+        vec![(&local_net.address("recipient").await, 100_000, None)],
+    )
+    .await
+    .unwrap();
+    local_net.generate_blocks_and_sync(1).await;
+
+    let recipient_balance = recipient.do_balance().await;
+    assert_eq!(recipient_balance.verified_orchard_balance, Some(100_000));
+}
+
+#[tokio::test]
+async fn local_net_with_clients_fund() {
+    tracing_subscriber::fmt().init();
+
+    // mining rewards must be paid to the zcashd wallet for funding
+    let local_net = LocalNet::<Zainod, Zcashd>::builder()
+        .validator_config(|config| config.miner_address = None)
+        .launch()
+        .await
+        .unwrap();
+    let local_net = LocalNetWithClients::new(
+        local_net,
+        vec![LightClientConfig {
+            pool: PoolType::Shielded(ShieldedProtocol::Sapling),
+            ..LightClientConfig::recipient()
+        }],
+    )
+    .await;
+
+    local_net
+        .fund("recipient", Zatoshis::from_u64(100_000).unwrap())
+        .await
+        .unwrap();
+
+    let recipient_balance = local_net
+        .lightclient("recipient")
+        .unwrap()
+        .do_balance()
+        .await;
+    assert_eq!(recipient_balance.verified_sapling_balance, Some(100_000));
+    assert_eq!(recipient_balance.verified_orchard_balance, Some(0));
+}
+
+#[tokio::test]
+async fn zainod_zebrad_mine_block_with() {
+    tracing_subscriber::fmt().init();
+
+    let local_net = LocalNet::<Zainod, Zebrad>::builder()
+        .launch()
+        .await
+        .unwrap();
+
+    let lightclient_dir = tempfile::tempdir().unwrap();
+    let (faucet, recipient) = client::build_lightclients(
+        lightclient_dir.path().to_path_buf(),
+        local_net.indexer().port(),
+        local_net.validator(),
+    )
+    .await;
+
+    let spendable_height = funding::coinbase_spendable_height(
+        local_net.validator().activation_heights(),
+        PoolType::Transparent,
+    );
+    let chain_height = local_net.validator().get_chain_height().await.unwrap();
+    local_net
+        .generate_blocks_and_sync(u32::from(spendable_height) - u32::from(chain_height))
+        .await
+        .unwrap();
+
+    faucet.do_sync(false).await.unwrap();
+    faucet.quick_shield().await.unwrap();
+    local_net.generate_blocks_and_sync(1).await.unwrap();
+
+    faucet.do_sync(false).await.unwrap();
+    let mut txids = Vec::new();
+    for pool in [ShieldedProtocol::Orchard, ShieldedProtocol::Sapling] {
+        let txid = *from_inputs::quick_send(
+            &faucet,
+            vec![(
+                &get_base_address(&recipient, PoolType::Shielded(pool)).await,
+                100_000,
+                None,
+            )],
+        )
+        .await
+        .unwrap()
+        .first();
+        txids.push(txid);
+    }
+    let sapling_transaction = local_net
+        .validator()
+        .get_raw_transaction(txids[1])
+        .await
+        .unwrap();
+
+    local_net
+        .validator()
+        .mine_block_with(vec![sapling_transaction], MempoolPolicy::Exclude)
+        .await
+        .unwrap();
+
+    let block = local_net
+        .validator()
+        .get_block(block::BlockId::Height(
+            local_net.validator().get_chain_height().await.unwrap(),
+        ))
+        .await
+        .unwrap();
+    let block_txids: Vec<_> = block
+        .transactions()
+        .iter()
+        .skip(1)
+        .map(|transaction| transaction.txid())
+        .collect();
+    assert_eq!(block_txids, vec![txids[1]]);
+    assert_eq!(
+        local_net.validator().get_raw_mempool().await.unwrap(),
+        vec![txids[0]]
+    );
+}
+
 #[tokio::test]
 async fn lightwalletd_zcashd_basic_send() {
     tracing_subscriber::fmt().init();
@@ -334,7 +1513,8 @@ async fn lightwalletd_zcashd_basic_send() {
         LightwalletdConfig {
             lightwalletd_bin: LIGHTWALLETD_BIN,
             listen_port: None,
-            zcashd_conf: PathBuf::new(),
+            zcashd_conf: None,
+            darkside: false,
         },
         ZcashdConfig {
             zcashd_bin: ZCASHD_BIN,
@@ -343,14 +1523,17 @@ async fn lightwalletd_zcashd_basic_send() {
             activation_heights: network::ActivationHeights::default(),
             miner_address: Some(REG_O_ADDR_FROM_ABANDONART),
             chain_cache: None,
+            network: network::Network::Regtest,
         },
     )
-    .await;
+    .await
+    .unwrap();
 
     let lightclient_dir = tempfile::tempdir().unwrap();
     let (faucet, recipient) = client::build_lightclients(
         lightclient_dir.path().to_path_buf(),
         local_net.indexer().port(),
+        local_net.validator(),
     )
     .await;
 
@@ -391,7 +1574,8 @@ async fn lightwalletd_zebrad_basic_send() {
         LightwalletdConfig {
             lightwalletd_bin: LIGHTWALLETD_BIN,
             listen_port: None,
-            zcashd_conf: PathBuf::new(),
+            zcashd_conf: None,
+            darkside: false,
         },
         ZebradConfig {
             zebrad_bin: ZEBRAD_BIN,
@@ -401,14 +1585,19 @@ async fn lightwalletd_zebrad_basic_send() {
             miner_address: ZEBRAD_DEFAULT_MINER,
             chain_cache: None,
             network: network::Network::Regtest,
+            metrics: false,
+            tracing_filter: None,
+            tracing_endpoint: false,
         },
     )
-    .await;
+    .await
+    .unwrap();
 
     let lightclient_dir = tempfile::tempdir().unwrap();
     let (faucet, recipient) = client::build_lightclients(
         lightclient_dir.path().to_path_buf(),
         local_net.indexer().port(),
+        local_net.validator(),
     )
     .await;
 